@@ -0,0 +1,128 @@
+//! Field type classification: `classify_field` against the per-type regexes it
+//! replaced, on every field of a generated 10,000-row sample.
+//!
+//! Run with `cargo bench --bench classify`.
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use csv_qsniffer::{DataType, classify_field};
+use regex::Regex;
+use std::hint::black_box;
+
+/// The built-in types in detector priority order, with the regexes
+/// `detect_data_type` ran one by one before the byte classifier
+const PATTERNS: [(DataType, &str); 11] = [
+    (
+        DataType::Boolean,
+        r"^(?i)(true|false|yes|no|y|n|1|0|on|off)$",
+    ),
+    (DataType::Integer, r"^[+-]?\d+$"),
+    (DataType::Float, r"^[+-]?(\d+\.?\d*|\.\d+)([eE][+-]?\d+)?$"),
+    (
+        DataType::DateTime,
+        r"^\d{1,4}[-/]\d{1,2}[-/]\d{1,4}\s+\d{1,2}:\d{2}(:\d{2})?",
+    ),
+    (DataType::Date, r"^\d{1,4}[-/]\d{1,2}[-/]\d{1,4}$"),
+    (DataType::Time, r"^\d{1,2}:\d{2}(:\d{2})?(\s?(AM|PM))?$"),
+    (
+        DataType::Email,
+        r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$",
+    ),
+    (DataType::Url, r"^https?://[^\s/$.?#].[^\s]*$"),
+    (DataType::Phone, r"^[\+]?[\d\s\-\(\)\.]{7,15}$"),
+    (
+        DataType::Currency,
+        r"^[\$£€¥]?[+-]?\d{1,3}(,\d{3})*(\.\d{2})?[\$£€¥]?$",
+    ),
+    (DataType::Percentage, r"^[+-]?\d+(\.\d+)?%$"),
+];
+
+/// Fields of a typical export: ids, amounts, dates, contacts and free text
+fn sample_fields(rows: usize) -> Vec<String> {
+    const WORDS: [&str; 8] = [
+        "alpha",
+        "Bravo",
+        "charlie",
+        "delta echo",
+        "Foxtrot",
+        "golf",
+        "hotel india",
+        "juliet",
+    ];
+    // xorshift, so every run sees the same sample
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    let mut fields = Vec::with_capacity(rows * 8);
+    for row in 0..rows {
+        let n = next();
+        fields.push((10_000 + row).to_string());
+        fields.push(format!("{}.{:02}", n % 100_000, n % 100));
+        fields.push(format!("2024-{:02}-{:02}", n % 12 + 1, n % 28 + 1));
+        fields.push(format!("2024-01-15 {:02}:{:02}:00", n % 24, n % 60));
+        fields.push(format!(
+            "{}{}@example.com",
+            WORDS[(n % 8) as usize].split(' ').next().unwrap(),
+            n % 1000
+        ));
+        fields.push(format!("+1 (555) {:03}-{:04}", n % 1000, n % 10_000));
+        fields.push(if n % 2 == 0 { "true" } else { "false" }.to_string());
+        fields.push(WORDS[(n >> 8) as usize % WORDS.len()].to_string());
+    }
+    fields
+}
+
+fn regex_type(regexes: &[(DataType, Regex)], field: &str) -> DataType {
+    regexes
+        .iter()
+        .find(|(_, regex)| regex.is_match(field))
+        .map_or(DataType::Text, |(data_type, _)| data_type.clone())
+}
+
+fn classifier_type(field: &str) -> DataType {
+    let types = classify_field(field);
+    PATTERNS
+        .iter()
+        .find(|(data_type, _)| types.contains(data_type))
+        .map_or(DataType::Text, |(data_type, _)| data_type.clone())
+}
+
+fn bench_classify(c: &mut Criterion) {
+    let fields = sample_fields(10_000);
+    let regexes: Vec<(DataType, Regex)> = PATTERNS
+        .iter()
+        .map(|(data_type, pattern)| (data_type.clone(), Regex::new(pattern).unwrap()))
+        .collect();
+    for field in &fields {
+        assert_eq!(
+            regex_type(&regexes, field),
+            classifier_type(field),
+            "{field}"
+        );
+    }
+
+    let mut group = c.benchmark_group("classify");
+    group.throughput(Throughput::Elements(fields.len() as u64));
+    group.bench_function("regex", |b| {
+        b.iter(|| {
+            for field in &fields {
+                black_box(regex_type(&regexes, black_box(field)));
+            }
+        });
+    });
+    group.bench_function("classify_field", |b| {
+        b.iter(|| {
+            for field in &fields {
+                black_box(classifier_type(black_box(field)));
+            }
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_classify);
+criterion_main!(benches);
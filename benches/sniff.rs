@@ -0,0 +1,221 @@
+//! End-to-end sniffing on generated files of several shapes: narrow and
+//! wide, short and long fields, quoted fields, fields spanning lines and a
+//! Latin-1 export, each at several `max_rows` values.
+//!
+//! Every shape is benchmarked through `sniff` (reading the full file, which
+//! stops after `max_rows` lines), `sniff_from_string` and the CLI's path of
+//! reading a file into memory and detecting from the string. The latter two
+//! score all the input they are given, so they get a file of `max_rows`
+//! records instead.
+//!
+//! Run with `cargo bench --bench sniff`, or e.g.
+//! `cargo bench --bench sniff -- 'sniff/wide'` for a subset.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use csv_qsniffer::{SniffResult, Sniffer, SnifferError};
+use std::fs::File;
+use std::hint::black_box;
+use std::io::{BufReader, Cursor, Read};
+use std::path::Path;
+use std::time::Duration;
+
+/// `max_rows` values each shape is sniffed at
+const MAX_ROWS: [usize; 3] = [10, 100, 1000];
+
+/// Rows generated per file, enough for `sniff` to stop early at every
+/// `max_rows` value
+const FILE_ROWS: usize = 2000;
+
+const WORDS: [&str; 8] = [
+    "alpha",
+    "Bravo",
+    "charlie",
+    "delta echo",
+    "Foxtrot",
+    "golf",
+    "hotel india",
+    "juliet",
+];
+
+#[derive(Debug, Clone, Copy)]
+enum Shape {
+    /// Four short columns of numbers, dates and booleans
+    Narrow,
+    /// 64 short columns
+    Wide,
+    /// An id, a title and a few hundred bytes of text per row
+    Long,
+    /// Semicolon-separated, with quoted fields containing the delimiter and
+    /// doubled quotes
+    Quoted,
+    /// Quoted comments spanning two lines in every third row
+    MultiLine,
+    /// Narrow rows with Latin-1 names from row 50 on, as spreadsheet exports
+    /// often are
+    Latin1,
+}
+
+impl Shape {
+    const ALL: [Self; 6] = [
+        Self::Narrow,
+        Self::Wide,
+        Self::Long,
+        Self::Quoted,
+        Self::MultiLine,
+        Self::Latin1,
+    ];
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Narrow => "narrow",
+            Self::Wide => "wide",
+            Self::Long => "long",
+            Self::Quoted => "quoted",
+            Self::MultiLine => "multi_line",
+            Self::Latin1 => "latin1",
+        }
+    }
+
+    const fn delimiter(self) -> u8 {
+        match self {
+            Self::Quoted => b';',
+            _ => b',',
+        }
+    }
+
+    /// A file of this shape with a header and `rows` records
+    fn generate(self, rows: usize) -> Vec<u8> {
+        // xorshift, so every run sees the same file
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let word = |n: u64| WORDS[(n % 8) as usize];
+
+        let mut out = Vec::new();
+        match self {
+            Self::Narrow | Self::Latin1 => out.extend(b"id,name,amount,date,active\n"),
+            Self::Wide => {
+                let header: Vec<String> = (1..=64).map(|col| format!("c{col}")).collect();
+                out.extend(header.join(",").as_bytes());
+                out.push(b'\n');
+            }
+            Self::Long => out.extend(b"id,title,body\n"),
+            Self::Quoted => out.extend(b"id;name;note;amount\n"),
+            Self::MultiLine => out.extend(b"id,comment,score\n"),
+        }
+        for row in 0..rows {
+            let n = next();
+            match self {
+                Self::Narrow | Self::Latin1 => {
+                    let name: &[u8] = match self {
+                        Self::Latin1 if row >= 50 && row % 3 == 0 => b"Jos\xe9",
+                        _ => word(n).as_bytes(),
+                    };
+                    out.extend(format!("{},", 10_000 + row).as_bytes());
+                    out.extend(name);
+                    out.extend(
+                        format!(
+                            ",{}.{:02},2024-{:02}-{:02},{}\n",
+                            n % 100_000,
+                            n % 100,
+                            n % 12 + 1,
+                            n % 28 + 1,
+                            n % 2 == 0
+                        )
+                        .as_bytes(),
+                    );
+                }
+                Self::Wide => {
+                    let fields: Vec<String> = (0..64_u64)
+                        .map(|col| match col % 4 {
+                            0 => (n.rotate_left(col as u32) % 1000).to_string(),
+                            1 => format!("{}.{}", n % 100, col),
+                            2 => word(n >> col).to_string(),
+                            _ => (n >> col & 1 == 0).to_string(),
+                        })
+                        .collect();
+                    out.extend(fields.join(",").as_bytes());
+                    out.push(b'\n');
+                }
+                Self::Long => {
+                    let body: Vec<&str> = (0..48).map(|i| word(n >> (i % 60))).collect();
+                    out.extend(format!("{row},{} {row},{}\n", word(n), body.join(" ")).as_bytes());
+                }
+                Self::Quoted => out.extend(
+                    format!(
+                        "{row};\"{}; {}\";\"said \"\"{}\"\"\";{}.{:02}\n",
+                        word(n),
+                        word(n >> 3),
+                        word(n >> 6),
+                        n % 1000,
+                        n % 100
+                    )
+                    .as_bytes(),
+                ),
+                Self::MultiLine => {
+                    let comment = if row % 3 == 0 {
+                        format!("\"{}\n{}\"", word(n), word(n >> 3))
+                    } else {
+                        word(n).to_string()
+                    };
+                    out.extend(format!("{row},{comment},{}\n", n % 10).as_bytes());
+                }
+            }
+        }
+        out
+    }
+}
+
+/// What the CLI does with a file argument: read it whole, then detect
+fn cli_detect(sniffer: &Sniffer, path: &Path) -> Result<SniffResult, SnifferError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut buffer = String::new();
+    reader.read_to_string(&mut buffer)?;
+    sniffer.detect_from_string(&buffer)
+}
+
+fn bench_sniff(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+
+    for shape in Shape::ALL {
+        let file = shape.generate(FILE_ROWS);
+        let mut group = c.benchmark_group(format!("sniff/{}", shape.name()));
+        group
+            .warm_up_time(Duration::from_secs(1))
+            .measurement_time(Duration::from_secs(3));
+
+        for max_rows in MAX_ROWS {
+            let mut sniffer = Sniffer::new();
+            sniffer.max_rows = max_rows;
+            let sample = shape.generate(max_rows);
+            // `sniff_from_string` needs text; a caller with Latin-1 input
+            // would decode it lossily
+            let text = String::from_utf8_lossy(&sample);
+            let path = dir.path().join(format!("{}-{max_rows}.csv", shape.name()));
+            std::fs::write(&path, &sample).unwrap();
+            let dialect = sniffer.sniff_from_string(&text).unwrap();
+            assert_eq!(dialect.delimiter, shape.delimiter(), "{shape:?} {max_rows}");
+
+            group.throughput(Throughput::Bytes(sample.len() as u64));
+            group.bench_with_input(BenchmarkId::new("sniff", max_rows), &file, |b, file| {
+                b.iter(|| black_box(sniffer.sniff(Cursor::new(black_box(file)))));
+            });
+            group.bench_with_input(
+                BenchmarkId::new("sniff_from_string", max_rows),
+                &text,
+                |b, text| b.iter(|| black_box(sniffer.sniff_from_string(black_box(text)))),
+            );
+            group.bench_with_input(BenchmarkId::new("cli", max_rows), &path, |b, path| {
+                b.iter(|| black_box(cli_detect(&sniffer, black_box(path))));
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_sniff);
+criterion_main!(benches);
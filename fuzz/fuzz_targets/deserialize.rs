@@ -0,0 +1,9 @@
+#![no_main]
+
+use csv_qsniffer::Sniffer;
+use libfuzzer_sys::fuzz_target;
+
+// Detection followed by a full read; errors are expected, panics are not
+fuzz_target!(|data: &[u8]| {
+    let _ = Sniffer::new().deserialize::<Vec<String>, _>(data);
+});
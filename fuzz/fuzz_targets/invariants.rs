@@ -0,0 +1,34 @@
+//! Invariants every successful detection must satisfy.
+
+use csv_qsniffer::SniffResult;
+
+/// Check that `result`, detected from `sample`, is self-consistent
+///
+/// - the score is finite
+/// - the detected dialect parses the whole sample with the csv crate, without
+///   `flexible` mode, into records as wide as the inferred column list
+pub fn check(sample: &str, result: &SniffResult) {
+    assert!(
+        result.score.is_finite(),
+        "non-finite score {}",
+        result.score
+    );
+
+    let mut reader = result
+        .dialect
+        .reader_builder()
+        .from_reader(sample.as_bytes());
+    for record in reader.records() {
+        let record = record.unwrap_or_else(|e| {
+            panic!(
+                "detected dialect ({}) fails to parse the sample: {e}",
+                result.dialect
+            )
+        });
+        assert_eq!(
+            record.len(),
+            result.columns.len(),
+            "record width differs from the inferred columns"
+        );
+    }
+}
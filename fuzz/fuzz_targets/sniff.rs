@@ -0,0 +1,29 @@
+#![no_main]
+
+use csv_qsniffer::Sniffer;
+use libfuzzer_sys::fuzz_target;
+
+mod invariants;
+
+// Byte-level entry point: arbitrary (possibly non-UTF-8) input through a reader
+fuzz_target!(|data: &[u8]| {
+    let sniffer = Sniffer::new();
+    let Ok(result) = sniffer.detect(data) else {
+        return;
+    };
+
+    // Readers reject invalid UTF-8, so a successful detection implies valid input
+    let text = std::str::from_utf8(data).expect("detected dialect of non-UTF-8 input");
+    invariants::check(&reader_sample(text, sniffer.max_rows), &result);
+
+    assert_eq!(sniffer.sniff(data).ok(), Some(result.dialect));
+});
+
+/// The sample `Sniffer::detect` builds from a reader: the first `max_rows`
+/// lines, each terminated by `\n`
+fn reader_sample(data: &str, max_rows: usize) -> String {
+    data.lines()
+        .take(max_rows)
+        .map(|line| format!("{line}\n"))
+        .collect()
+}
@@ -0,0 +1,16 @@
+#![no_main]
+
+use csv_qsniffer::Sniffer;
+use libfuzzer_sys::fuzz_target;
+
+mod invariants;
+
+fuzz_target!(|data: &str| {
+    let sniffer = Sniffer::new();
+    let Ok(result) = sniffer.detect_from_string(data) else {
+        return;
+    };
+
+    invariants::check(data, &result);
+    assert_eq!(sniffer.sniff_from_string(data).ok(), Some(result.dialect));
+});
@@ -0,0 +1,202 @@
+//! Arrow schema inference from column profiles (requires the `arrow` feature).
+
+use crate::{ColumnProfile, DataType, Dialect, SniffResult};
+use arrow_schema::{DECIMAL128_MAX_PRECISION, DataType as ArrowType, Field, Schema, TimeUnit};
+
+/// CSV reader options derived from a dialect
+///
+/// Mirrors the settings of arrow-csv's `Format` and `DataFusion`'s `CsvFormat`,
+/// so either can be configured without depending on them here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArrowCsvOptions {
+    pub has_header: bool,
+    pub delimiter: u8,
+    /// `None` disables quoting
+    pub quote: Option<u8>,
+    pub escape: Option<u8>,
+    /// `None` means CRLF-or-LF line endings
+    pub terminator: Option<u8>,
+}
+
+impl Dialect {
+    /// Reader options for arrow-csv / `DataFusion` matching this dialect
+    #[must_use]
+    pub fn arrow_csv_options(&self) -> ArrowCsvOptions {
+        ArrowCsvOptions {
+            has_header: self.has_headers,
+            delimiter: self.delimiter,
+            quote: self.quote_char,
+            escape: self.escape,
+            terminator: match self.terminator {
+                csv::Terminator::Any(b'\n') | csv::Terminator::CRLF => None,
+                csv::Terminator::Any(b) => Some(b),
+                _ => None,
+            },
+        }
+    }
+}
+
+impl ColumnProfile {
+    /// Arrow type for values of this column
+    ///
+    /// Floats with the same number of fraction digits throughout become
+    /// `Decimal128`, as do integers too long for `Int64`. Date-times become
+    /// timestamps whose unit matches the longest fractional seconds seen.
+    /// Values arrow-csv cannot parse stay `Utf8`: booleans other than
+    /// `true`/`false`, dates and date-times not in ISO 8601 form (or on a
+    /// 12-hour clock), and currency and percentage columns, whose values
+    /// carry symbols or thousands separators.
+    #[must_use]
+    pub fn arrow_data_type(&self) -> ArrowType {
+        match self.data_type {
+            DataType::Integer if self.max_integer_digits <= 18 => ArrowType::Int64,
+            DataType::Integer => self.decimal_type().unwrap_or(ArrowType::Utf8),
+            DataType::Float if self.fixed_scale && self.max_fraction_digits > 0 => {
+                self.decimal_type().unwrap_or(ArrowType::Float64)
+            }
+            DataType::Float => ArrowType::Float64,
+            DataType::Boolean if self.standard_form => ArrowType::Boolean,
+            DataType::Date if self.standard_form => ArrowType::Date32,
+            DataType::DateTime if self.standard_form => {
+                let unit = match self.max_fraction_digits {
+                    0 => TimeUnit::Second,
+                    1..=3 => TimeUnit::Millisecond,
+                    4..=6 => TimeUnit::Microsecond,
+                    _ => TimeUnit::Nanosecond,
+                };
+                ArrowType::Timestamp(unit, None)
+            }
+            _ => ArrowType::Utf8,
+        }
+    }
+
+    /// `Decimal128` holding every digit seen, if it fits
+    fn decimal_type(&self) -> Option<ArrowType> {
+        let precision = u8::try_from(self.max_integer_digits + self.max_fraction_digits).ok()?;
+        let scale = i8::try_from(self.max_fraction_digits).ok()?;
+        (1..=DECIMAL128_MAX_PRECISION)
+            .contains(&precision)
+            .then_some(ArrowType::Decimal128(precision, scale))
+    }
+}
+
+impl SniffResult {
+    /// Build an Arrow schema from the inferred column profiles
+    ///
+    /// All fields are nullable, since empty values may appear beyond the sample.
+    #[must_use]
+    pub fn arrow_schema(&self) -> Schema {
+        Schema::new(
+            self.columns
+                .iter()
+                .map(|column| Field::new(&column.name, column.arrow_data_type(), true))
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sniffer;
+
+    #[test]
+    fn test_arrow_schema() {
+        let csv_data = "id|price|ratio|active|day|seen_at|note|share\n\
+                        1|$1,234.50|0.5|true|2024-01-02|2024-01-02 10:00:00.125|a|50%\n\
+                        2|$80.00|1.25|false|2024-01-03|2024-01-03 11:30:00.5|b|12.5%\n\
+                        3|$5.25|2.0|true|2024-01-04|2024-01-04 12:45:00|c|7%";
+        let result = Sniffer::new().detect_from_string(csv_data).unwrap();
+        let schema = result.arrow_schema();
+
+        let types: Vec<&ArrowType> = schema.fields().iter().map(|f| f.data_type()).collect();
+        assert_eq!(
+            types,
+            [
+                &ArrowType::Int64,
+                // arrow-csv cannot parse "$1,234.50" or "50%" as numbers
+                &ArrowType::Utf8,
+                &ArrowType::Float64,
+                &ArrowType::Boolean,
+                &ArrowType::Date32,
+                &ArrowType::Timestamp(TimeUnit::Millisecond, None),
+                &ArrowType::Utf8,
+                &ArrowType::Utf8,
+            ]
+        );
+        assert_eq!(schema.field(1).name(), "price");
+        assert!(schema.fields().iter().all(|f| f.is_nullable()));
+
+        let options = result.dialect.arrow_csv_options();
+        assert_eq!(options.delimiter, b'|');
+        assert!(options.has_header);
+        assert_eq!(options.terminator, None);
+    }
+
+    /// Convert an inferred type to the arrow-schema release arrow-csv uses
+    fn arrow_csv_type(data_type: &ArrowType) -> arrow_schema_54::DataType {
+        use arrow_schema_54::{DataType as CsvType, TimeUnit as CsvUnit};
+
+        match data_type {
+            ArrowType::Int64 => CsvType::Int64,
+            ArrowType::Float64 => CsvType::Float64,
+            ArrowType::Decimal128(precision, scale) => CsvType::Decimal128(*precision, *scale),
+            ArrowType::Boolean => CsvType::Boolean,
+            ArrowType::Date32 => CsvType::Date32,
+            ArrowType::Timestamp(unit, None) => CsvType::Timestamp(
+                match unit {
+                    TimeUnit::Second => CsvUnit::Second,
+                    TimeUnit::Millisecond => CsvUnit::Millisecond,
+                    TimeUnit::Microsecond => CsvUnit::Microsecond,
+                    TimeUnit::Nanosecond => CsvUnit::Nanosecond,
+                },
+                None,
+            ),
+            ArrowType::Utf8 => CsvType::Utf8,
+            other => panic!("unexpected inferred type {other}"),
+        }
+    }
+
+    #[test]
+    fn test_arrow_csv_reads_sample_with_inferred_schema() {
+        let csv_data = "id,amount,ratio,active,answer,day,us_day,seen_at,clock\n\
+                        1,12.50,0.5,true,yes,2024-01-02,01/02/2024,2024-01-02 10:00:00.125,2024-01-02 10:00 AM\n\
+                        2,3.25,1.25,false,no,2024-01-03,01/03/2024,2024-01-03 11:30:00.5,2024-01-03 11:30 AM\n\
+                        3,100.00,2.0,TRUE,yes,2024-01-04,01/04/2024,2024-01-04 12:45:00,2024-01-04 12:45 PM\n";
+        let result = Sniffer::new().detect_from_string(csv_data).unwrap();
+        let schema = result.arrow_schema();
+
+        let types: Vec<&ArrowType> = schema.fields().iter().map(|f| f.data_type()).collect();
+        assert_eq!(
+            types,
+            [
+                &ArrowType::Int64,
+                &ArrowType::Decimal128(5, 2),
+                &ArrowType::Float64,
+                &ArrowType::Boolean,
+                // arrow-csv only reads true/false, ISO dates and 24-hour times
+                &ArrowType::Utf8,
+                &ArrowType::Date32,
+                &ArrowType::Utf8,
+                &ArrowType::Timestamp(TimeUnit::Millisecond, None),
+                &ArrowType::Utf8,
+            ]
+        );
+
+        let csv_schema = arrow_schema_54::Schema::new(
+            schema
+                .fields()
+                .iter()
+                .map(|f| arrow_schema_54::Field::new(f.name(), arrow_csv_type(f.data_type()), true))
+                .collect::<Vec<_>>(),
+        );
+        let options = result.dialect.arrow_csv_options();
+        let reader = arrow_csv::ReaderBuilder::new(std::sync::Arc::new(csv_schema))
+            .with_header(options.has_header)
+            .with_delimiter(options.delimiter)
+            .build(csv_data.as_bytes())
+            .unwrap();
+        let rows: usize = reader.map(|batch| batch.unwrap().num_rows()).sum();
+        assert_eq!(rows, 3);
+    }
+}
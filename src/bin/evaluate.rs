@@ -0,0 +1,185 @@
+#![cfg(feature = "evaluate")]
+
+use clap::{Parser, ValueEnum};
+use csv_qsniffer::{
+    AttributeMetrics, ConsistencyScorer, Dialect, FrequencyScorer, ScoringProfile, Sniffer,
+    TableUniformityScorer, load_annotated_corpus, load_corpus,
+};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(name = "csv-qsniffer-evaluate")]
+#[command(about = "Measure csv-qsniffer's accuracy on a corpus of annotated CSV files")]
+#[command(version)]
+struct Cli {
+    /// Directory of CSV files
+    #[arg(value_name = "CORPUS_DIR")]
+    corpus: PathBuf,
+
+    /// Tab-separated CSVsniffer-style annotation file (file, delimiter, quotechar,
+    /// escapechar); without it, each file needs a JSON dialect sidecar (data.csv + data.json)
+    #[arg(short, long, value_name = "FILE")]
+    annotations: Option<PathBuf>,
+
+    /// Dialect scoring algorithm
+    #[arg(long, value_enum, default_value_t = ScorerKind::Tum)]
+    scorer: ScorerKind,
+
+    /// Scoring profile: a preset (default, sparse, text-heavy) or a .json/.toml file
+    #[arg(long, value_name = "PRESET|FILE")]
+    profile: Option<String>,
+
+    /// Maximum number of rows of each file to analyze
+    #[arg(long, default_value_t = 1000)]
+    max_rows: usize,
+
+    /// Exit with status 2 when accuracy (in percent) falls below this
+    #[arg(long, value_name = "PERCENT")]
+    min_accuracy: Option<f64>,
+
+    /// List every misdetected file
+    #[arg(short, long)]
+    verbose: bool,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum ScorerKind {
+    /// Table Uniformity Method
+    Tum,
+    /// `CleverCSV` pattern x type consistency
+    Clevercsv,
+    /// Python `csv.Sniffer`-compatible frequency heuristic
+    Python,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+
+    let mut sniffer = Sniffer::new();
+    sniffer.max_rows = cli.max_rows;
+    match cli.scorer {
+        ScorerKind::Tum => sniffer.set_scorer(TableUniformityScorer),
+        ScorerKind::Clevercsv => sniffer.set_scorer(ConsistencyScorer),
+        ScorerKind::Python => sniffer.set_scorer(FrequencyScorer),
+    };
+    if let Some(profile) = &cli.profile {
+        sniffer.scoring_profile = match ScoringProfile::preset(profile) {
+            Some(preset) => preset,
+            None => ScoringProfile::load(profile)?,
+        };
+    }
+
+    let corpus = match &cli.annotations {
+        Some(annotations) => load_annotated_corpus(&cli.corpus, annotations)?,
+        None => load_corpus(&cli.corpus)?,
+    };
+    if corpus.is_empty() {
+        eprintln!(
+            "Error: No annotated CSV files found in {}",
+            cli.corpus.display()
+        );
+        std::process::exit(1);
+    }
+
+    let evaluation = sniffer.evaluate(&corpus);
+
+    println!(
+        "Accuracy: {:.2}% ({}/{} files)",
+        evaluation.accuracy() * 100.0,
+        evaluation.correct,
+        evaluation.total
+    );
+    println!("Mean F1:  {:.2}%", evaluation.f1() * 100.0);
+    println!();
+    println!(
+        "{:<12} {:>9} {:>9} {:>9}",
+        "Attribute", "Precision", "Recall", "F1"
+    );
+    for (name, metrics) in [
+        ("delimiter", &evaluation.delimiter),
+        ("quotechar", &evaluation.quote_char),
+        ("escapechar", &evaluation.escape),
+    ] {
+        print_metrics(name, metrics);
+    }
+
+    // Delimiter confusion matrix: annotated delimiters as rows, detected as columns
+    let expected: BTreeSet<u8> = evaluation.delimiter_confusion.keys().map(|k| k.0).collect();
+    let detected: BTreeSet<Option<u8>> =
+        evaluation.delimiter_confusion.keys().map(|k| k.1).collect();
+    println!();
+    println!("Delimiter confusion (rows: annotated, columns: detected)");
+    print!("{:<10}", "");
+    for column in &detected {
+        print!(" {:>8}", char_name(*column));
+    }
+    println!();
+    for row in &expected {
+        print!("{:<10}", char_name(Some(*row)));
+        for column in &detected {
+            let count = evaluation
+                .delimiter_confusion
+                .get(&(*row, *column))
+                .copied()
+                .unwrap_or(0);
+            print!(" {count:>8}");
+        }
+        println!();
+    }
+
+    if cli.verbose && !evaluation.mismatches.is_empty() {
+        println!();
+        println!("Misdetected files:");
+        for mismatch in &evaluation.mismatches {
+            println!(
+                "  {}: expected {}, detected {}",
+                mismatch.path.display(),
+                describe(&mismatch.expected),
+                mismatch
+                    .detected
+                    .as_ref()
+                    .map_or_else(|| "nothing".to_string(), describe)
+            );
+        }
+    }
+
+    if let Some(min_accuracy) = cli.min_accuracy
+        && evaluation.accuracy() * 100.0 < min_accuracy
+    {
+        eprintln!("Error: accuracy below {min_accuracy:.2}%");
+        std::process::exit(2);
+    }
+
+    Ok(())
+}
+
+fn print_metrics(name: &str, metrics: &AttributeMetrics) {
+    println!(
+        "{:<12} {:>8.2}% {:>8.2}% {:>8.2}%",
+        name,
+        metrics.precision() * 100.0,
+        metrics.recall() * 100.0,
+        metrics.f1() * 100.0
+    );
+}
+
+/// Short display name for a dialect character; `None` means detection failed
+fn char_name(c: Option<u8>) -> String {
+    match c {
+        None => "(failed)".to_string(),
+        Some(b'\t') => "tab".to_string(),
+        Some(b' ') => "space".to_string(),
+        Some(b) => format!("'{}'", b as char),
+    }
+}
+
+fn describe(dialect: &Dialect) -> String {
+    let optional = |c: Option<u8>| c.map_or_else(|| "none".to_string(), |c| char_name(Some(c)));
+    format!(
+        "delimiter {}, quote {}, escape {}",
+        char_name(Some(dialect.delimiter)),
+        optional(dialect.quote_char),
+        optional(dialect.escape)
+    )
+}
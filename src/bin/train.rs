@@ -0,0 +1,72 @@
+#![cfg(feature = "train")]
+
+use clap::Parser;
+use csv_qsniffer::{ScoringProfile, Sniffer, TrainingOptions, load_corpus};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(name = "csv-qsniffer-train")]
+#[command(about = "Fit csv-qsniffer scoring weights to a corpus of annotated CSV files")]
+#[command(version)]
+struct Cli {
+    /// Directory of CSV files, each annotated by a JSON dialect sidecar (data.csv + data.json)
+    #[arg(value_name = "CORPUS_DIR")]
+    corpus: PathBuf,
+
+    /// Where to write the fitted profile (.json or .toml)
+    #[arg(short, long, default_value = "profile.toml")]
+    output: PathBuf,
+
+    /// Starting profile: a preset (default, sparse, text-heavy) or a .json/.toml file
+    #[arg(long, value_name = "PRESET|FILE", default_value = "default")]
+    base: String,
+
+    /// Maximum number of rows of each file to analyze
+    #[arg(long, default_value_t = 1000)]
+    max_rows: usize,
+
+    /// Maximum number of coordinate search sweeps
+    #[arg(long, default_value_t = TrainingOptions::default().max_iterations)]
+    max_iterations: usize,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+
+    let mut sniffer = Sniffer::new();
+    sniffer.max_rows = cli.max_rows;
+    sniffer.scoring_profile = match ScoringProfile::preset(&cli.base) {
+        Some(preset) => preset,
+        None => ScoringProfile::load(&cli.base)?,
+    };
+
+    let corpus = load_corpus(&cli.corpus)?;
+    if corpus.is_empty() {
+        eprintln!(
+            "Error: No annotated CSV files found in {}",
+            cli.corpus.display()
+        );
+        std::process::exit(1);
+    }
+
+    let options = TrainingOptions {
+        max_iterations: cli.max_iterations,
+        ..TrainingOptions::default()
+    };
+    let report = sniffer.train_profile(&corpus, &options);
+    report.profile.save(&cli.output)?;
+
+    eprintln!(
+        "Trained on {} files ({} skipped: annotated dialect never parsed cleanly)",
+        report.examples, report.skipped
+    );
+    eprintln!(
+        "Accuracy: {:.1}% -> {:.1}% after {} sweeps",
+        report.initial_accuracy * 100.0,
+        report.accuracy * 100.0,
+        report.iterations
+    );
+    eprintln!("Wrote {}", cli.output.display());
+
+    Ok(())
+}
@@ -0,0 +1,259 @@
+//! Memoization shared by every candidate parse of one sniff.
+//!
+//! Candidate dialects that differ only in a quote character the sample never
+//! contains split it into exactly the same records, so each such group is
+//! parsed and typed once. Field types are cached by content, as the same
+//! fields recur across candidates and repeated values within a column are
+//! common; columns that rarely repeat a value bypass the cache.
+
+use crate::{CandidateTable, DataType, Dialect, Rejection};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError, RwLock, RwLockReadGuard};
+
+/// Fields longer than this are rarely repeated and are not cached
+const MAX_CACHED_FIELD_LEN: usize = 32;
+
+/// Lookups per column after which a column whose fields mostly missed the
+/// cache (identifiers, measurements) stops using it
+const SAMPLED_LOOKUPS: u32 = 64;
+
+/// Dialect parameters that determine how a sample is tokenized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct TableKey {
+    delimiter: u8,
+    /// `None` also when the quote character does not occur in the sample
+    quote: Option<u8>,
+    escape: Option<u8>,
+    /// Line terminator byte; `None` for CRLF
+    terminator: Option<u8>,
+    has_headers: bool,
+}
+
+type TableCell = Arc<OnceLock<Result<CandidateTable, Rejection>>>;
+/// Field types by content
+///
+/// Fields come from untrusted input, so the map keeps the randomly seeded
+/// default hasher: with a fixed hash, a sample of colliding values, each
+/// repeated to stay in the cache, would make every lookup linear.
+type TypeMap = HashMap<Box<str>, DataType>;
+
+/// Per-sniff cache of candidate tables and field types
+pub(crate) struct SniffCache {
+    /// Which bytes occur in the sample
+    present: [bool; 256],
+    tables: Mutex<HashMap<TableKey, TableCell>>,
+    types: RwLock<TypeMap>,
+}
+
+impl SniffCache {
+    pub(crate) fn new(data: &str) -> Self {
+        let mut present = [false; 256];
+        for &b in data.as_bytes() {
+            present[usize::from(b)] = true;
+        }
+        Self {
+            present,
+            tables: Mutex::default(),
+            types: RwLock::default(),
+        }
+    }
+
+    /// The table of a dialect, parsing it only if no dialect that tokenizes
+    /// the sample identically has been parsed yet
+    ///
+    /// Concurrent callers with the same tokenization wait for a single parse.
+    pub(crate) fn table(
+        &self,
+        dialect: &Dialect,
+        parse: impl FnOnce() -> Result<CandidateTable, Rejection>,
+    ) -> Result<CandidateTable, Rejection> {
+        let key = TableKey {
+            delimiter: dialect.delimiter,
+            quote: dialect
+                .quote_char
+                .filter(|&quote| self.present[usize::from(quote)]),
+            escape: dialect.escape,
+            terminator: match dialect.terminator {
+                csv::Terminator::Any(b) => Some(b),
+                _ => None,
+            },
+            has_headers: dialect.has_headers,
+        };
+        let cell = Arc::clone(
+            self.tables
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .entry(key)
+                .or_default(),
+        );
+        cell.get_or_init(parse).clone()
+    }
+
+    /// Start looking up field types for one candidate parse
+    pub(crate) fn field_types(&self) -> FieldTypes<'_> {
+        FieldTypes {
+            cache: self,
+            seen: Some(self.types.read().unwrap_or_else(PoisonError::into_inner)),
+            new: TypeMap::default(),
+            columns: Vec::new(),
+        }
+    }
+}
+
+/// Field type lookups of one candidate parse
+///
+/// Holds the shared types for reading and keeps newly detected ones aside,
+/// merging them into the cache when dropped, so a parse locks it only twice.
+pub(crate) struct FieldTypes<'a> {
+    cache: &'a SniffCache,
+    seen: Option<RwLockReadGuard<'a, TypeMap>>,
+    new: TypeMap,
+    /// Lookups and misses per column
+    columns: Vec<(u32, u32)>,
+}
+
+impl FieldTypes<'_> {
+    /// The data type of a field in a column, detecting it on first sight
+    ///
+    /// Long fields, and fields of columns that rarely repeat a value, are
+    /// detected every time.
+    pub(crate) fn get(
+        &mut self,
+        column: usize,
+        field: &str,
+        detect: impl FnOnce(&str) -> DataType,
+    ) -> DataType {
+        if column >= self.columns.len() {
+            self.columns.resize(column + 1, (0, 0));
+        }
+        let (lookups, misses) = &mut self.columns[column];
+        if field.len() > MAX_CACHED_FIELD_LEN
+            || (*lookups >= SAMPLED_LOOKUPS && *misses * 2 > *lookups)
+        {
+            return detect(field);
+        }
+        *lookups += 1;
+        let seen = self.seen.as_ref().and_then(|seen| seen.get(field));
+        if let Some(data_type) = seen.or_else(|| self.new.get(field)) {
+            return data_type.clone();
+        }
+        *misses += 1;
+        let data_type = detect(field);
+        self.new.insert(field.into(), data_type.clone());
+        data_type
+    }
+}
+
+impl Drop for FieldTypes<'_> {
+    fn drop(&mut self) {
+        drop(self.seen.take());
+        if !self.new.is_empty() {
+            self.cache
+                .types
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .extend(self.new.drain());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sniffer;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_absent_quote_shares_unquoted_table() {
+        let data = "a,b\n1,'2'\n3,'4'\n";
+        let cache = SniffCache::new(data);
+        let sniffer = Sniffer::new();
+        let mut parses = 0;
+        let mut table = |quote_char| {
+            let dialect = Dialect {
+                quote_char,
+                ..Dialect::default()
+            };
+            cache
+                .table(&dialect, || {
+                    parses += 1;
+                    sniffer.parse_with_dialect(data, &dialect, &cache)
+                })
+                .unwrap()
+        };
+
+        let unquoted = table(None);
+        let double = table(Some(b'"'));
+        let single = table(Some(b'\''));
+        assert_eq!(parses, 2);
+        assert_eq!(double.column_type_counts, unquoted.column_type_counts);
+        assert_eq!(single.column_type_counts[1][&DataType::Integer], 2);
+        assert_eq!(unquoted.column_type_counts[1][&DataType::Text], 2);
+    }
+
+    #[test]
+    fn test_field_types_are_detected_once() {
+        let cache = SniffCache::new("");
+        let sniffer = Sniffer::new();
+        let detections = Cell::new(0);
+        let detect = |field: &str| {
+            detections.set(detections.get() + 1);
+            sniffer.detect_data_type(field)
+        };
+        let mut types = cache.field_types();
+        for field in ["12", "x", "12"] {
+            types.get(0, field, detect);
+        }
+        drop(types);
+
+        // A later parse sees the first one's types
+        let mut types = cache.field_types();
+        assert_eq!(types.get(0, "12", |_| unreachable!()), DataType::Integer);
+        types.get(0, "y", detect);
+        drop(types);
+        assert_eq!(detections.get(), 3);
+
+        // Columns of distinct values stop being cached
+        let mut types = cache.field_types();
+        for n in 0..100 {
+            types.get(1, &format!("id-{n}"), detect);
+        }
+        drop(types);
+        assert_eq!(detections.get(), 103);
+        assert_eq!(cache.types.read().unwrap().len(), 3 + 64);
+    }
+
+    #[test]
+    fn test_field_hash_is_seeded_per_cache() {
+        use std::hash::BuildHasher;
+
+        // Cacheable fields sharing their first and last 8 bytes, each repeated
+        // often enough that the column keeps using the cache
+        let fields: Vec<String> = (0..10_000)
+            .map(|n| format!("user-id-{n:015}-tail-end"))
+            .collect();
+        assert!(
+            fields
+                .iter()
+                .all(|field| field.len() <= MAX_CACHED_FIELD_LEN)
+        );
+        let caches = [SniffCache::new(""), SniffCache::new("")];
+        for cache in &caches {
+            let mut types = cache.field_types();
+            for field in fields.iter().flat_map(|field| [field, field, field]) {
+                types.get(0, field, |_| DataType::Text);
+            }
+        }
+
+        // Keys that collide under one cache's hasher need not under another's
+        let hashes = caches.each_ref().map(|cache| {
+            let types = cache.types.read().unwrap();
+            assert_eq!(types.len(), fields.len());
+            fields
+                .iter()
+                .map(|field| types.hasher().hash_one(field.as_str()))
+                .collect::<Vec<_>>()
+        });
+        assert_ne!(hashes[0], hashes[1]);
+    }
+}
@@ -0,0 +1,612 @@
+//! Single-pass classification of a field against every built-in `DataType`.
+//!
+//! One scan over the field's bytes records which kinds of bytes occur. Each
+//! built-in type that is still possible is then confirmed by a hand-written
+//! matcher, equivalent to the type's pattern below; most fields are ruled out
+//! for most types by the scan alone. Whitespace is Unicode whitespace (as in
+//! the regex `\s`), but `\d` stands for an ASCII digit only: unlike the
+//! Unicode-aware regexes used before 0.2, other decimal digits such as
+//! Arabic-Indic `١٢٣` are not numbers, so such fields are `Text`.
+//!
+//! | Type | Pattern |
+//! |------|---------|
+//! | `Integer` | `^[+-]?\d+$` |
+//! | `Float` | `^[+-]?(\d+\.?\d*\|\.\d+)([eE][+-]?\d+)?$` |
+//! | `Boolean` | `^(?i)(true\|false\|yes\|no\|y\|n\|1\|0\|on\|off)$` |
+//! | `Date` | `^\d{1,4}[-/]\d{1,2}[-/]\d{1,4}$` |
+//! | `Time` | `^\d{1,2}:\d{2}(:\d{2})?(\s?(AM\|PM))?$` |
+//! | `DateTime` | `^\d{1,4}[-/]\d{1,2}[-/]\d{1,4}\s+\d{1,2}:\d{2}` (prefix) |
+//! | `Email` | `^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$` |
+//! | `Url` | `^https?://[^\s/$.?#].[^\s]*$` |
+//! | `Phone` | `^[\+]?[\d\s\-\(\)\.]{7,15}$` |
+//! | `Currency` | `^[\$£€¥]?[+-]?\d{1,3}(,\d{3})*(\.\d{2})?[\$£€¥]?$` |
+//! | `Percentage` | `^[+-]?\d+(\.\d+)?%$` |
+
+use crate::DataType;
+
+/// The built-in types a field matches
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TypeSet(u16);
+
+/// Built-in types with a pattern, in bit order
+const BUILTIN_TYPES: [DataType; 11] = [
+    DataType::Integer,
+    DataType::Float,
+    DataType::Boolean,
+    DataType::Date,
+    DataType::Time,
+    DataType::DateTime,
+    DataType::Email,
+    DataType::Url,
+    DataType::Phone,
+    DataType::Currency,
+    DataType::Percentage,
+];
+
+const fn bit(data_type: &DataType) -> u16 {
+    match data_type {
+        DataType::Integer => 1,
+        DataType::Float => 1 << 1,
+        DataType::Boolean => 1 << 2,
+        DataType::Date => 1 << 3,
+        DataType::Time => 1 << 4,
+        DataType::DateTime => 1 << 5,
+        DataType::Email => 1 << 6,
+        DataType::Url => 1 << 7,
+        DataType::Phone => 1 << 8,
+        DataType::Currency => 1 << 9,
+        DataType::Percentage => 1 << 10,
+        _ => 0,
+    }
+}
+
+impl TypeSet {
+    /// Whether the field matched `data_type`; always false for `Text`,
+    /// `Empty` and custom types
+    #[must_use]
+    pub const fn contains(self, data_type: &DataType) -> bool {
+        self.0 & bit(data_type) != 0
+    }
+
+    /// Whether the field matched no built-in type
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The matched types, in `DataType` order
+    pub fn iter(self) -> impl Iterator<Item = DataType> {
+        BUILTIN_TYPES
+            .into_iter()
+            .filter(move |data_type| self.contains(data_type))
+    }
+
+    fn insert(&mut self, data_type: &DataType, matched: bool) {
+        if matched {
+            self.0 |= bit(data_type);
+        }
+    }
+}
+
+// Byte classes recorded by the scan
+const DIGIT: u16 = 1;
+const PLUS: u16 = 1 << 1;
+const MINUS: u16 = 1 << 2;
+const DOT: u16 = 1 << 3;
+const EXPONENT: u16 = 1 << 4;
+const SLASH: u16 = 1 << 5;
+const COLON: u16 = 1 << 6;
+const SPACE: u16 = 1 << 7;
+const PERCENT: u16 = 1 << 8;
+const COMMA: u16 = 1 << 9;
+const PAREN: u16 = 1 << 10;
+const MERIDIEM: u16 = 1 << 11;
+const DOLLAR: u16 = 1 << 12;
+const AT: u16 = 1 << 13;
+/// Part of a multi-byte character: Unicode whitespace or a currency symbol
+const NON_ASCII: u16 = 1 << 14;
+const OTHER: u16 = 1 << 15;
+
+const fn byte_classes() -> [u16; 256] {
+    let mut classes = [OTHER; 256];
+    let mut b = 0;
+    while b < 256 {
+        classes[b] = match b as u8 {
+            b'0'..=b'9' => DIGIT,
+            b'+' => PLUS,
+            b'-' => MINUS,
+            b'.' => DOT,
+            b'e' | b'E' => EXPONENT,
+            b'/' => SLASH,
+            b':' => COLON,
+            b' ' | b'\t' | b'\n' | b'\x0b' | b'\x0c' | b'\r' => SPACE,
+            b'%' => PERCENT,
+            b',' => COMMA,
+            b'(' | b')' => PAREN,
+            b'A' | b'M' | b'P' => MERIDIEM,
+            b'$' => DOLLAR,
+            b'@' => AT,
+            0x80..=0xff => NON_ASCII,
+            _ => OTHER,
+        };
+        b += 1;
+    }
+    classes
+}
+
+static BYTE_CLASSES: [u16; 256] = byte_classes();
+
+/// Whether every class in `mask` is one of `allowed`
+const fn only(mask: u16, allowed: u16) -> bool {
+    mask & !allowed == 0
+}
+
+/// Find every built-in type whose pattern matches `field` (as given; callers
+/// trim fields first)
+#[must_use]
+pub fn classify_field(field: &str) -> TypeSet {
+    let mut types = TypeSet::default();
+    let bytes = field.as_bytes();
+    if bytes.is_empty() {
+        return types;
+    }
+
+    let mut mask = 0;
+    let mut digits = 0;
+    for &b in bytes {
+        let class = BYTE_CLASSES[usize::from(b)];
+        mask |= class;
+        digits += usize::from(class == DIGIT);
+    }
+
+    if bytes.len() <= 5 {
+        types.insert(&DataType::Boolean, is_boolean(field));
+    }
+    if mask & AT != 0 {
+        types.insert(&DataType::Email, is_email(bytes));
+    }
+    if bytes.starts_with(b"http") {
+        types.insert(&DataType::Url, is_url(field));
+    }
+    if digits == 0 {
+        return types;
+    }
+
+    if only(mask, DIGIT | PLUS | MINUS) {
+        let signed = matches!(bytes[0], b'+' | b'-');
+        if digits + usize::from(signed) == bytes.len() {
+            types.insert(&DataType::Integer, true);
+            types.insert(&DataType::Float, true);
+            types.insert(&DataType::Currency, digits <= 3);
+            types.insert(&DataType::Phone, bytes.len() >= 7 && is_phone(field));
+            return types;
+        }
+    }
+
+    if only(mask, DIGIT | PLUS | MINUS | DOT | EXPONENT) {
+        types.insert(&DataType::Float, is_float(bytes));
+    }
+    if only(mask, DIGIT | MINUS | SLASH) {
+        types.insert(&DataType::Date, is_date(bytes));
+    }
+    if mask & COLON != 0 {
+        if mask & (MINUS | SLASH) != 0 && mask & (SPACE | NON_ASCII) != 0 {
+            types.insert(&DataType::DateTime, is_date_time(field));
+        }
+        if only(mask, DIGIT | COLON | SPACE | MERIDIEM | NON_ASCII) {
+            types.insert(&DataType::Time, is_time(field));
+        }
+    }
+    if mask & PERCENT != 0 && only(mask, DIGIT | PLUS | MINUS | DOT | PERCENT) {
+        types.insert(&DataType::Percentage, is_percentage(bytes));
+    }
+    if bytes.len() >= 7 && only(mask, DIGIT | PLUS | MINUS | DOT | SPACE | PAREN | NON_ASCII) {
+        types.insert(&DataType::Phone, is_phone(field));
+    }
+    if only(
+        mask,
+        DIGIT | PLUS | MINUS | DOT | COMMA | DOLLAR | NON_ASCII,
+    ) {
+        types.insert(&DataType::Currency, is_currency(field));
+    }
+    types
+}
+
+/// Cursor over ASCII bytes
+struct Scan<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Scan<'a> {
+    const fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, accept: impl Fn(u8) -> bool) -> bool {
+        match self.peek() {
+            Some(b) if accept(b) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn eat_sign(&mut self) {
+        self.eat(|b| b == b'+' || b == b'-');
+    }
+
+    /// Consume up to `max` digits and return how many were consumed
+    fn digits(&mut self, max: usize) -> usize {
+        let count = self.bytes[self.pos..]
+            .iter()
+            .take(max)
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        self.pos += count;
+        count
+    }
+
+    const fn done(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        &self.bytes[self.pos..]
+    }
+}
+
+fn is_float(bytes: &[u8]) -> bool {
+    let mut scan = Scan::new(bytes);
+    scan.eat_sign();
+    let integer_digits = scan.digits(usize::MAX);
+    let fraction_digits = if scan.eat(|b| b == b'.') {
+        scan.digits(usize::MAX)
+    } else {
+        0
+    };
+    if integer_digits == 0 && fraction_digits == 0 {
+        return false;
+    }
+    if scan.eat(|b| b == b'e' || b == b'E') {
+        scan.eat_sign();
+        if scan.digits(usize::MAX) == 0 {
+            return false;
+        }
+    }
+    scan.done()
+}
+
+fn is_boolean(field: &str) -> bool {
+    [
+        "true", "false", "yes", "no", "y", "n", "1", "0", "on", "off",
+    ]
+    .iter()
+    .any(|word| field.eq_ignore_ascii_case(word))
+}
+
+/// `\d{1,4}[-/]\d{1,2}[-/]\d{1,4}` at the start of `scan`
+fn date_prefix(scan: &mut Scan<'_>) -> bool {
+    let separator = |b| b == b'-' || b == b'/';
+    (1..=4).contains(&scan.digits(4))
+        && scan.eat(separator)
+        && (1..=2).contains(&scan.digits(2))
+        && scan.eat(separator)
+        && (1..=4).contains(&scan.digits(4))
+}
+
+fn is_date(bytes: &[u8]) -> bool {
+    let mut scan = Scan::new(bytes);
+    date_prefix(&mut scan) && scan.done()
+}
+
+/// `\d{1,2}:\d{2}` at the start of `scan`
+fn hours_minutes(scan: &mut Scan<'_>) -> bool {
+    (1..=2).contains(&scan.digits(2)) && scan.eat(|b| b == b':') && scan.digits(2) == 2
+}
+
+fn is_date_time(field: &str) -> bool {
+    let mut scan = Scan::new(field.as_bytes());
+    if !date_prefix(&mut scan) {
+        return false;
+    }
+    let rest = &field[scan.pos..];
+    let time = rest.trim_start();
+    time.len() < rest.len() && hours_minutes(&mut Scan::new(time.as_bytes()))
+}
+
+fn is_time(field: &str) -> bool {
+    let mut scan = Scan::new(field.as_bytes());
+    if !hours_minutes(&mut scan) {
+        return false;
+    }
+    if scan.eat(|b| b == b':') && scan.digits(2) != 2 {
+        return false;
+    }
+
+    let rest = &field[scan.pos..];
+    let mut chars = rest.chars();
+    let meridiem = match chars.next() {
+        Some(c) if c.is_whitespace() => chars.as_str(),
+        _ => rest,
+    };
+    rest.is_empty() || meridiem == "AM" || meridiem == "PM"
+}
+
+fn is_email(bytes: &[u8]) -> bool {
+    let Some(at) = bytes.iter().position(|&b| b == b'@') else {
+        return false;
+    };
+    let (local, domain) = (&bytes[..at], &bytes[at + 1..]);
+    let Some(dot) = domain.iter().rposition(|&b| b == b'.') else {
+        return false;
+    };
+
+    !local.is_empty()
+        && local
+            .iter()
+            .all(|&b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'%' | b'+' | b'-'))
+        && dot > 0
+        && domain[..dot]
+            .iter()
+            .all(|&b| b.is_ascii_alphanumeric() || b == b'.' || b == b'-')
+        && domain.len() - dot > 2
+        && domain[dot + 1..].iter().all(u8::is_ascii_alphabetic)
+}
+
+fn is_url(field: &str) -> bool {
+    let Some(rest) = field
+        .strip_prefix("https://")
+        .or_else(|| field.strip_prefix("http://"))
+    else {
+        return false;
+    };
+
+    let mut chars = rest.chars();
+    matches!(chars.next(), Some(c) if !c.is_whitespace() && !matches!(c, '/' | '$' | '.' | '?' | '#'))
+        && matches!(chars.next(), Some(c) if c != '\n')
+        && chars.all(|c| !c.is_whitespace())
+}
+
+fn is_phone(field: &str) -> bool {
+    let body = field.strip_prefix('+').unwrap_or(field);
+    let mut count = 0;
+    for c in body.chars() {
+        if !(c.is_ascii_digit() || c.is_whitespace() || matches!(c, '-' | '(' | ')' | '.')) {
+            return false;
+        }
+        count += 1;
+    }
+    (7..=15).contains(&count)
+}
+
+fn is_currency(field: &str) -> bool {
+    let is_symbol = |c: char| matches!(c, '$' | '£' | '€' | '¥');
+    let body = field.strip_prefix(is_symbol).unwrap_or(field);
+    let body = body.strip_suffix(is_symbol).unwrap_or(body);
+
+    let mut scan = Scan::new(body.as_bytes());
+    scan.eat_sign();
+    if scan.digits(3) == 0 {
+        return false;
+    }
+    while scan.eat(|b| b == b',') {
+        if scan.digits(3) != 3 {
+            return false;
+        }
+    }
+    if scan.eat(|b| b == b'.') && scan.digits(2) != 2 {
+        return false;
+    }
+    scan.done()
+}
+
+fn is_percentage(bytes: &[u8]) -> bool {
+    let mut scan = Scan::new(bytes);
+    scan.eat_sign();
+    if scan.digits(usize::MAX) == 0 {
+        return false;
+    }
+    if scan.eat(|b| b == b'.') && scan.digits(usize::MAX) == 0 {
+        return false;
+    }
+    scan.rest() == b"%"
+}
+
+/// `\d{4}-\d{1,2}-\d{1,2}` at the start of `scan`
+fn iso_date_prefix(scan: &mut Scan<'_>) -> bool {
+    scan.digits(4) == 4
+        && scan.eat(|b| b == b'-')
+        && (1..=2).contains(&scan.digits(2))
+        && scan.eat(|b| b == b'-')
+        && (1..=2).contains(&scan.digits(2))
+}
+
+/// Whether a field already classified as `data_type` is in that type's
+/// standard form: `true`/`false` for booleans, ISO 8601 for dates, and
+/// ISO 8601 on a 24-hour clock (`2024-01-02 10:00:00[.fff][Z]`) for
+/// date-times. Other types have no narrower standard form.
+pub(crate) fn is_standard_form(field: &str, data_type: &DataType) -> bool {
+    let mut scan = Scan::new(field.as_bytes());
+    match data_type {
+        DataType::Boolean => {
+            field.eq_ignore_ascii_case("true") || field.eq_ignore_ascii_case("false")
+        }
+        DataType::Date => iso_date_prefix(&mut scan) && scan.done(),
+        DataType::DateTime => {
+            if !(iso_date_prefix(&mut scan)
+                && scan.eat(|b| b == b' ' || b == b'T')
+                && scan.digits(2) == 2
+                && scan.eat(|b| b == b':')
+                && scan.digits(2) == 2
+                && scan.eat(|b| b == b':')
+                && scan.digits(2) == 2)
+            {
+                return false;
+            }
+            if scan.eat(|b| b == b'.') && scan.digits(usize::MAX) == 0 {
+                return false;
+            }
+            scan.eat(|b| b == b'Z');
+            scan.done()
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    /// The patterns from the module documentation, as regexes
+    fn reference() -> Vec<(DataType, Regex)> {
+        [
+            (DataType::Integer, r"^[+-]?\d+$"),
+            (DataType::Float, r"^[+-]?(\d+\.?\d*|\.\d+)([eE][+-]?\d+)?$"),
+            (
+                DataType::Boolean,
+                r"^(?i)(true|false|yes|no|y|n|1|0|on|off)$",
+            ),
+            (DataType::Date, r"^\d{1,4}[-/]\d{1,2}[-/]\d{1,4}$"),
+            (DataType::Time, r"^\d{1,2}:\d{2}(:\d{2})?(\s?(AM|PM))?$"),
+            (
+                DataType::DateTime,
+                r"^\d{1,4}[-/]\d{1,2}[-/]\d{1,4}\s+\d{1,2}:\d{2}(:\d{2})?",
+            ),
+            (
+                DataType::Email,
+                r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$",
+            ),
+            (DataType::Url, r"^https?://[^\s/$.?#].[^\s]*$"),
+            (DataType::Phone, r"^[\+]?[\d\s\-\(\)\.]{7,15}$"),
+            (
+                DataType::Currency,
+                r"^[\$£€¥]?[+-]?\d{1,3}(,\d{3})*(\.\d{2})?[\$£€¥]?$",
+            ),
+            (DataType::Percentage, r"^[+-]?\d+(\.\d+)?%$"),
+        ]
+        .into_iter()
+        .map(|(data_type, pattern)| {
+            // `\d` is an ASCII digit, as in the module documentation
+            let pattern = pattern.replace(r"\d", "[0-9]");
+            (data_type, Regex::new(&pattern).unwrap())
+        })
+        .collect()
+    }
+
+    fn assert_matches_reference(reference: &[(DataType, Regex)], field: &str) {
+        let types = classify_field(field);
+        for (data_type, regex) in reference {
+            assert_eq!(
+                types.contains(data_type),
+                regex.is_match(field),
+                "{data_type:?} on {field:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_classifier_matches_patterns() {
+        let reference = reference();
+        for field in [
+            "42",
+            "-7",
+            "+3.5",
+            ".5",
+            "5.",
+            "1e10",
+            "2.5E-3",
+            "e5",
+            "1e",
+            "TRUE",
+            "Off",
+            "y",
+            "2024-01-15",
+            "15/01/2024",
+            "2024-1-5",
+            "12345-01-01",
+            "2024-01-15 10:30",
+            "2024-01-15\t9:05:00",
+            "2024-01-15T10:30",
+            "2024-01-15 10:3",
+            "9:30",
+            "09:30:15",
+            "12:30 PM",
+            "12:30PM",
+            "12:30\u{a0}AM",
+            "12:30 pm",
+            "12:345",
+            "a@b.co",
+            "a@b.c",
+            "@b.co",
+            "a@.co",
+            "x.y+z@mail.example.org",
+            "a@b@c.com",
+            "http://x.y",
+            "https://example.com/path?q=1",
+            "http:///x",
+            "http://a b",
+            "http://a bc d",
+            "http://a\u{2003}b",
+            "+1 (555) 123-4567",
+            "555-1234",
+            "123456",
+            "-123456",
+            "+1234567",
+            "1234567890123456",
+            "$1,234.56",
+            "1,234",
+            "€12",
+            "12€",
+            "$-5.00",
+            "1,23",
+            "1234,567",
+            "$1.5",
+            "12.5%",
+            "-3%",
+            "%",
+            "5.%",
+            "",
+            " ",
+            "text",
+            "١٢٣",
+            "٢٠٢٤-٠١-١٥",
+        ] {
+            assert_matches_reference(&reference, field);
+        }
+    }
+
+    #[test]
+    fn test_only_ascii_digits_are_digits() {
+        // Arabic-Indic and fullwidth digits
+        for field in ["١٢٣", "١٢٫٥", "٢٠٢٤-٠١-١٥", "１２３"] {
+            assert!(classify_field(field).is_empty(), "{field:?}");
+        }
+        assert!(classify_field("123").contains(&DataType::Integer));
+    }
+
+    #[test]
+    fn test_classifier_matches_patterns_exhaustively() {
+        // Every string of up to four characters over the patterns' significant characters
+        let reference = reference();
+        let alphabet = [
+            "0", "1", "9", ".", "-", "+", "/", ":", "e", "a", "@", "%", ",", "$", "€", " ",
+        ];
+        let mut fields = vec![String::new()];
+        for _ in 0..4 {
+            fields = fields
+                .iter()
+                .flat_map(|field| alphabet.iter().map(move |c| format!("{field}{c}")))
+                .collect();
+            for field in &fields {
+                assert_matches_reference(&reference, field);
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! Labeled CSV corpora for training and evaluating dialect detection
+//! (requires the `json` feature).
+
+use crate::{Dialect, SnifferError};
+use std::path::{Path, PathBuf};
+
+/// A CSV file with its ground-truth dialect
+#[derive(Debug, Clone)]
+pub struct LabeledSample {
+    /// Path of the CSV file
+    pub path: PathBuf,
+    /// File contents, with invalid UTF-8 replaced
+    pub data: String,
+    /// The annotated dialect
+    pub dialect: Dialect,
+}
+
+impl LabeledSample {
+    /// Whether `dialect` has the annotated delimiter, quote and escape characters
+    ///
+    /// Header detection is not part of the dialect proper, so `has_headers` is ignored.
+    #[must_use]
+    pub fn matches(&self, dialect: &Dialect) -> bool {
+        dialect.delimiter == self.dialect.delimiter
+            && dialect.quote_char == self.dialect.quote_char
+            && dialect.escape == self.dialect.escape
+    }
+}
+
+/// Load every annotated CSV file in `dir`, sorted by path
+///
+/// A file is annotated by a JSON sidecar with the same stem (`sales.csv` →
+/// `sales.json`) holding a serialized `Dialect`, e.g.
+/// `{"delimiter": ";", "quote_char": "'"}`; omitted fields take their defaults.
+/// Files without a sidecar are skipped.
+pub fn load_corpus<P: AsRef<Path>>(dir: P) -> Result<Vec<LabeledSample>, SnifferError> {
+    let mut paths = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.sort();
+
+    let mut samples = Vec::new();
+    for path in paths {
+        let is_annotation = path.extension().is_some_and(|ext| ext == "json");
+        let annotation = path.with_extension("json");
+        if !path.is_file() || is_annotation || !annotation.is_file() {
+            continue;
+        }
+
+        let dialect =
+            serde_json::from_str(&std::fs::read_to_string(&annotation)?).map_err(|e| {
+                SnifferError::InvalidAnnotation {
+                    path: annotation.clone(),
+                    message: e.to_string(),
+                }
+            })?;
+        let data = String::from_utf8_lossy(&std::fs::read(&path)?).into_owned();
+        samples.push(LabeledSample {
+            path,
+            data,
+            dialect,
+        });
+    }
+
+    Ok(samples)
+}
+
+/// Load the CSV files listed in a CSVsniffer-style annotation file
+///
+/// Each non-empty line not starting with `#` names a file relative to `dir`
+/// followed by its delimiter, quote character and escape character, separated
+/// by tabs. Characters may be written literally (`;`, `"`), escaped (`\t`), or
+/// by name (`comma`, `semicolon`, `tab`, `space`, `pipe`, `colon`,
+/// `doublequote`, `singlequote`, `backslash`); `none` or an empty or missing
+/// field means no quote or escape character. A first line starting with
+/// `file` is treated as a header.
+pub fn load_annotated_corpus<P: AsRef<Path>, A: AsRef<Path>>(
+    dir: P,
+    annotations: A,
+) -> Result<Vec<LabeledSample>, SnifferError> {
+    let annotations = annotations.as_ref();
+    let invalid = |line: usize, message: String| SnifferError::InvalidAnnotation {
+        path: annotations.to_path_buf(),
+        message: format!("line {line}: {message}"),
+    };
+
+    let mut samples = Vec::new();
+    for (idx, line) in std::fs::read_to_string(annotations)?.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty()
+            || line.starts_with('#')
+            || (idx == 0 && line.to_ascii_lowercase().starts_with("file"))
+        {
+            continue;
+        }
+
+        let mut fields = line.split('\t');
+        let name = fields.next().unwrap_or_default().trim();
+        let mut next_char = || parse_char(fields.next().unwrap_or_default());
+        let delimiter = next_char()
+            .map_err(|e| invalid(idx + 1, e))?
+            .ok_or_else(|| invalid(idx + 1, "missing delimiter".to_string()))?;
+        let quote_char = next_char().map_err(|e| invalid(idx + 1, e))?;
+        let escape = next_char().map_err(|e| invalid(idx + 1, e))?;
+
+        let path = dir.as_ref().join(name);
+        let data = std::fs::read(&path)
+            .map_err(|e| invalid(idx + 1, format!("{}: {e}", path.display())))?;
+        samples.push(LabeledSample {
+            path,
+            data: String::from_utf8_lossy(&data).into_owned(),
+            dialect: Dialect {
+                delimiter,
+                quote_char,
+                escape,
+                ..Dialect::default()
+            },
+        });
+    }
+
+    Ok(samples)
+}
+
+/// Parse one annotated character
+fn parse_char(token: &str) -> Result<Option<u8>, String> {
+    let byte = match token.trim_matches(|c| c == ' ' || c == '\r') {
+        "" if token.contains(' ') => b' ',
+        "" | "none" | "null" => return Ok(None),
+        "comma" => b',',
+        "semicolon" => b';',
+        "tab" | "\\t" => b'\t',
+        "space" => b' ',
+        "pipe" => b'|',
+        "colon" => b':',
+        "doublequote" | "double quote" => b'"',
+        "singlequote" | "single quote" | "apostrophe" => b'\'',
+        "backslash" | "\\\\" => b'\\',
+        other => match other.as_bytes() {
+            [byte] => *byte,
+            _ => return Err(format!("unrecognized character '{other}'")),
+        },
+    };
+    Ok(Some(byte))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_corpus() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.csv"), "x;y\n1;2\n").unwrap();
+        std::fs::write(
+            dir.path().join("a.json"),
+            r#"{"delimiter": ";", "quote_char": null}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("unlabeled.csv"), "x,y\n1,2\n").unwrap();
+
+        let corpus = load_corpus(dir.path()).unwrap();
+        assert_eq!(corpus.len(), 1);
+        assert_eq!(corpus[0].dialect.delimiter, b';');
+        assert!(corpus[0].matches(&Dialect {
+            delimiter: b';',
+            quote_char: None,
+            has_headers: false,
+            ..Dialect::default()
+        }));
+
+        std::fs::write(
+            dir.path().join("annotations.txt"),
+            "file\tdelimiter\tquotechar\tescapechar\n\
+             a.csv\tsemicolon\tdoublequote\tnone\n\
+             unlabeled.csv\t,\t'\t\\\\\n",
+        )
+        .unwrap();
+        let corpus = load_annotated_corpus(dir.path(), dir.path().join("annotations.txt")).unwrap();
+        assert_eq!(corpus.len(), 2);
+        assert_eq!(corpus[0].dialect.delimiter, b';');
+        assert_eq!(corpus[1].dialect.quote_char, Some(b'\''));
+        assert_eq!(corpus[1].dialect.escape, Some(b'\\'));
+        assert_eq!(parse_char(" "), Ok(Some(b' ')));
+        assert!(parse_char("??").is_err());
+
+        std::fs::write(dir.path().join("unlabeled.json"), "{").unwrap();
+        assert!(matches!(
+            load_corpus(dir.path()),
+            Err(SnifferError::InvalidAnnotation { .. })
+        ));
+    }
+}
@@ -0,0 +1,185 @@
+//! `CREATE TABLE` generation from inferred column profiles.
+
+use crate::{DataType, SniffResult};
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// Maximum identifier length; PostgreSQL truncates anything longer
+const MAX_IDENTIFIER_LEN: usize = 63;
+
+/// SQL dialects supported by `SniffResult::create_table`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlDialect {
+    Postgres,
+    Sqlite,
+    DuckDb,
+}
+
+impl SqlDialect {
+    /// Column type used for values of `data_type`
+    #[must_use]
+    pub const fn column_type(self, data_type: &DataType) -> &'static str {
+        match (self, data_type) {
+            (Self::Postgres | Self::DuckDb, DataType::Integer) => "BIGINT",
+            (Self::Sqlite, DataType::Integer | DataType::Boolean) => "INTEGER",
+            (Self::Postgres, DataType::Float) => "DOUBLE PRECISION",
+            (Self::Sqlite, DataType::Float) => "REAL",
+            (Self::DuckDb, DataType::Float) => "DOUBLE",
+            (_, DataType::Currency | DataType::Percentage) => "NUMERIC",
+            (Self::Postgres | Self::DuckDb, DataType::Boolean) => "BOOLEAN",
+            (Self::Postgres | Self::DuckDb, DataType::Date) => "DATE",
+            (Self::Postgres | Self::DuckDb, DataType::Time) => "TIME",
+            (Self::Postgres | Self::DuckDb, DataType::DateTime) => "TIMESTAMP",
+            (Self::DuckDb, _) => "VARCHAR",
+            // SQLite has no date/time storage classes; ISO 8601 text is the convention
+            _ => "TEXT",
+        }
+    }
+}
+
+/// Turn an arbitrary header into a lowercase `[a-z0-9_]` identifier
+///
+/// Returns `None` when nothing usable remains, so callers can substitute a
+/// positional name.
+fn sanitize_identifier(name: &str) -> Option<String> {
+    let mut ident = String::with_capacity(name.len());
+    for c in name.trim().chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            ident.push(c);
+        } else if !ident.ends_with('_') {
+            ident.push('_');
+        }
+    }
+
+    let ident = ident.trim_matches('_');
+    if ident.is_empty() {
+        return None;
+    }
+
+    let mut ident = if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{ident}")
+    } else {
+        ident.to_string()
+    };
+    ident.truncate(MAX_IDENTIFIER_LEN);
+    Some(ident)
+}
+
+/// The identifier `SniffResult::create_table` uses for a table named `name`
+///
+/// Use it wherever else the table is referenced, e.g. in a `COPY` statement,
+/// so the statements agree.
+#[must_use]
+pub fn sql_table_name(name: &str) -> String {
+    sanitize_identifier(name).unwrap_or_else(|| "data".to_string())
+}
+
+impl SniffResult {
+    /// Generate `CREATE TABLE` DDL for a staging table holding this data
+    ///
+    /// Table and column names are sanitized to lowercase identifiers (deduplicated
+    /// with numeric suffixes) and double-quoted. All columns are nullable.
+    #[must_use]
+    pub fn create_table(&self, table: &str, dialect: SqlDialect) -> String {
+        let table = sql_table_name(table);
+        let mut seen = HashSet::new();
+        let mut ddl = format!("CREATE TABLE \"{table}\" (\n");
+
+        for (idx, column) in self.columns.iter().enumerate() {
+            let base =
+                sanitize_identifier(&column.name).unwrap_or_else(|| format!("column_{}", idx + 1));
+            let mut name = base.clone();
+            let mut suffix = 2;
+            while !seen.insert(name.clone()) {
+                // Keep the suffix within the identifier limit, or truncation
+                // would make the names collide again
+                let suffix_str = format!("_{suffix}");
+                let prefix = &base[..base.len().min(MAX_IDENTIFIER_LEN - suffix_str.len())];
+                name = format!("{prefix}{suffix_str}");
+                suffix += 1;
+            }
+
+            let separator = if idx + 1 < self.columns.len() {
+                ","
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                ddl,
+                "    \"{name}\" {}{separator}",
+                dialect.column_type(&column.data_type)
+            );
+        }
+
+        ddl.push_str(");");
+        ddl
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sniffer;
+
+    #[test]
+    fn test_sanitize_identifier() {
+        assert_eq!(sanitize_identifier("Order ID").as_deref(), Some("order_id"));
+        assert_eq!(
+            sanitize_identifier("  Total ($) ").as_deref(),
+            Some("total")
+        );
+        assert_eq!(
+            sanitize_identifier("2024 Sales").as_deref(),
+            Some("_2024_sales")
+        );
+        assert_eq!(
+            sanitize_identifier("\"; DROP TABLE x; --").as_deref(),
+            Some("drop_table_x")
+        );
+        assert_eq!(sanitize_identifier("%%"), None);
+        assert_eq!(sql_table_name("Sales Data"), "sales_data");
+        assert_eq!(sql_table_name("%%"), "data");
+    }
+
+    #[test]
+    fn test_create_table_deduplicates_long_names_within_limit() {
+        let long = "x".repeat(70);
+        let csv_data = format!("{long},{long},{long}\n1,2,3\n4,5,6\n");
+        let result = Sniffer::new().detect_from_string(&csv_data).unwrap();
+        let ddl = result.create_table("t", SqlDialect::Postgres);
+
+        let names: Vec<&str> = ddl
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.split('"').nth(1))
+            .collect();
+        assert_eq!(names.len(), 3);
+        assert!(names.iter().all(|name| name.len() <= MAX_IDENTIFIER_LEN));
+        assert_eq!(names.iter().collect::<HashSet<_>>().len(), 3);
+        assert!(names[1].ends_with("x_2"));
+    }
+
+    #[test]
+    fn test_create_table() {
+        let csv_data = "Order ID,order id,Amount,Placed,\n\
+                        1001,a,$1.50,2024-01-02,note\n\
+                        1002,b,$2.00,2024-01-03,\n\
+                        1003,c,$10.25,2024-01-04,memo";
+        let result = Sniffer::new().detect_from_string(csv_data).unwrap();
+
+        assert_eq!(
+            result.create_table("Orders 2024", SqlDialect::Postgres),
+            "CREATE TABLE \"orders_2024\" (\n    \
+             \"order_id\" BIGINT,\n    \
+             \"order_id_2\" TEXT,\n    \
+             \"amount\" NUMERIC,\n    \
+             \"placed\" DATE,\n    \
+             \"column_5\" TEXT\n);"
+        );
+
+        let sqlite = result.create_table("orders", SqlDialect::Sqlite);
+        assert!(sqlite.contains("\"placed\" TEXT"));
+        let duckdb = result.create_table("orders", SqlDialect::DuckDb);
+        assert!(duckdb.contains("\"order_id_2\" VARCHAR"));
+    }
+}
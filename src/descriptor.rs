@@ -0,0 +1,340 @@
+//! Frictionless Data and W3C CSVW descriptors for detected dialects.
+//!
+//! Descriptors are plain serde structs using the camelCase key names of the
+//! respective specifications, so they can be written out with any serde format.
+
+use crate::{ColumnProfile, DataType, Dialect, SniffResult};
+use serde::{Deserialize, Serialize};
+
+/// Boolean spellings accepted by the built-in boolean detector
+const TRUE_VALUES: [&str; 12] = [
+    "true", "True", "TRUE", "yes", "Yes", "YES", "y", "Y", "1", "on", "On", "ON",
+];
+const FALSE_VALUES: [&str; 12] = [
+    "false", "False", "FALSE", "no", "No", "NO", "n", "N", "0", "off", "Off", "OFF",
+];
+
+/// Frictionless Data CSV dialect descriptor
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrictionlessDialect {
+    pub delimiter: String,
+    pub line_terminator: String,
+    /// Omitted when the data is unquoted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote_char: Option<String>,
+    pub double_quote: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escape_char: Option<String>,
+    pub header: bool,
+    /// 1-based row numbers that make up the header, counting preamble lines
+    pub header_rows: Vec<usize>,
+    /// Prefix of lines to skip as comments: `#` when a preamble was skipped,
+    /// and empty otherwise, as detected dialects read every line as data
+    #[serde(default)]
+    pub comment_char: String,
+}
+
+/// Frictionless Table Schema built from inferred column types
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableSchema {
+    pub fields: Vec<SchemaField>,
+    pub missing_values: Vec<String>,
+}
+
+/// A single Table Schema field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bare_number: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_char: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub true_values: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub false_values: Vec<String>,
+}
+
+/// Frictionless dialect, optionally with a Table Schema
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrictionlessDescriptor {
+    pub dialect: FrictionlessDialect,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<TableSchema>,
+}
+
+/// W3C CSVW dialect description
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvwDialect {
+    pub delimiter: String,
+    /// `null` when the data is unquoted
+    pub quote_char: Option<String>,
+    /// `false` when quotes are escaped with a backslash instead of doubled
+    pub double_quote: bool,
+    pub header: bool,
+    pub header_row_count: usize,
+    pub skip_rows: usize,
+    /// Prefix of lines to skip as comments; empty, as detected dialects
+    /// read every line as data. Always written, since CSVW processors
+    /// otherwise default to `#` and drop data rows starting with it.
+    #[serde(default)]
+    pub comment_prefix: String,
+    pub line_terminators: Vec<String>,
+}
+
+/// W3C CSVW table schema built from inferred column types
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CsvwTableSchema {
+    pub columns: Vec<CsvwColumn>,
+}
+
+/// A single CSVW column description
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CsvwColumn {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub titles: Option<String>,
+    pub datatype: String,
+}
+
+/// CSVW metadata document with the dialect and, optionally, a table schema
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CsvwDescriptor {
+    #[serde(rename = "@context")]
+    pub context: String,
+    pub dialect: CsvwDialect,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table_schema: Option<CsvwTableSchema>,
+}
+
+fn char_string(b: u8) -> String {
+    char::from(b).to_string()
+}
+
+fn line_terminator(terminator: csv::Terminator) -> String {
+    match terminator {
+        csv::Terminator::Any(b) => char_string(b),
+        _ => "\r\n".to_string(),
+    }
+}
+
+impl From<&Dialect> for FrictionlessDialect {
+    fn from(dialect: &Dialect) -> Self {
+        Self {
+            delimiter: char_string(dialect.delimiter),
+            line_terminator: line_terminator(dialect.terminator),
+            quote_char: dialect.quote_char.map(char_string),
+            double_quote: dialect.escape.is_none(),
+            escape_char: dialect.escape.map(char_string),
+            header: dialect.has_headers,
+            header_rows: if dialect.has_headers {
+                vec![1]
+            } else {
+                Vec::new()
+            },
+            comment_char: String::new(),
+        }
+    }
+}
+
+impl From<&Dialect> for CsvwDialect {
+    fn from(dialect: &Dialect) -> Self {
+        Self {
+            delimiter: char_string(dialect.delimiter),
+            quote_char: dialect.quote_char.map(char_string),
+            double_quote: dialect.escape.is_none(),
+            header: dialect.has_headers,
+            header_row_count: usize::from(dialect.has_headers),
+            skip_rows: 0,
+            comment_prefix: String::new(),
+            line_terminators: vec![line_terminator(dialect.terminator)],
+        }
+    }
+}
+
+impl From<&ColumnProfile> for SchemaField {
+    fn from(column: &ColumnProfile) -> Self {
+        let (field_type, format) = match column.data_type {
+            DataType::Integer => ("integer", None),
+            DataType::Float | DataType::Currency | DataType::Percentage => ("number", None),
+            DataType::Boolean => ("boolean", None),
+            DataType::Date => ("date", Some("any")),
+            DataType::Time => ("time", Some("any")),
+            DataType::DateTime => ("datetime", Some("any")),
+            DataType::Email => ("string", Some("email")),
+            DataType::Url => ("string", Some("uri")),
+            DataType::Phone | DataType::Text | DataType::Empty | DataType::Custom(_) => {
+                ("string", None)
+            }
+        };
+        let is_boolean = column.data_type == DataType::Boolean;
+        let values = |values: &[&str]| {
+            if is_boolean {
+                values.iter().map(ToString::to_string).collect()
+            } else {
+                Vec::new()
+            }
+        };
+
+        Self {
+            name: column.name.clone(),
+            field_type: field_type.to_string(),
+            format: format.map(str::to_string),
+            bare_number: matches!(column.data_type, DataType::Currency | DataType::Percentage)
+                .then_some(false),
+            group_char: (column.data_type == DataType::Currency).then(|| ",".to_string()),
+            true_values: values(&TRUE_VALUES),
+            false_values: values(&FALSE_VALUES),
+        }
+    }
+}
+
+impl CsvwColumn {
+    fn from_profile(column: &ColumnProfile, has_headers: bool) -> Self {
+        let datatype = match column.data_type {
+            DataType::Integer => "integer",
+            DataType::Float => "double",
+            DataType::Boolean => "boolean",
+            DataType::Date => "date",
+            DataType::Time => "time",
+            DataType::DateTime => "dateTime",
+            DataType::Url => "anyURI",
+            // Currency symbols, grouping and percent signs need CSVW number
+            // patterns that vary per file, so these stay strings
+            DataType::Currency
+            | DataType::Percentage
+            | DataType::Email
+            | DataType::Phone
+            | DataType::Text
+            | DataType::Empty
+            | DataType::Custom(_) => "string",
+        };
+
+        Self {
+            name: column.name.clone(),
+            titles: has_headers.then(|| column.name.clone()),
+            datatype: datatype.to_string(),
+        }
+    }
+}
+
+impl SniffResult {
+    /// Build a Frictionless Table Schema from the inferred column types
+    #[must_use]
+    pub fn table_schema(&self) -> TableSchema {
+        TableSchema {
+            fields: self.columns.iter().map(SchemaField::from).collect(),
+            missing_values: vec![String::new()],
+        }
+    }
+
+    /// Export the detected dialect as a Frictionless descriptor, optionally
+    /// including the inferred Table Schema
+    #[must_use]
+    pub fn to_frictionless(&self, include_schema: bool) -> FrictionlessDescriptor {
+        let dialect = FrictionlessDialect::from(&self.dialect);
+        FrictionlessDescriptor {
+            dialect: FrictionlessDialect {
+                header_rows: dialect
+                    .header_rows
+                    .iter()
+                    .map(|row| row + self.preamble_rows)
+                    .collect(),
+                comment_char: if self.preamble_rows > 0 {
+                    "#".to_string()
+                } else {
+                    String::new()
+                },
+                ..dialect
+            },
+            schema: include_schema.then(|| self.table_schema()),
+        }
+    }
+
+    /// Export the detected dialect as CSVW metadata, optionally including the
+    /// inferred table schema
+    #[must_use]
+    pub fn to_csvw(&self, include_schema: bool) -> CsvwDescriptor {
+        CsvwDescriptor {
+            context: "http://www.w3.org/ns/csvw".to_string(),
+            dialect: CsvwDialect {
+                skip_rows: self.preamble_rows,
+                ..CsvwDialect::from(&self.dialect)
+            },
+            table_schema: include_schema.then(|| CsvwTableSchema {
+                columns: self
+                    .columns
+                    .iter()
+                    .map(|column| CsvwColumn::from_profile(column, self.dialect.has_headers))
+                    .collect(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Sniffer;
+
+    #[test]
+    fn test_frictionless_descriptor() {
+        let csv_data = "id;price;active\n10;$1,200.50;yes\n11;$80.00;no\n12;$5.25;yes";
+        let result = Sniffer::new().detect_from_string(csv_data).unwrap();
+        let json = serde_json::to_value(result.to_frictionless(true)).unwrap();
+
+        assert_eq!(json["dialect"]["delimiter"], ";");
+        assert_eq!(json["dialect"]["quoteChar"], "\"");
+        assert_eq!(json["dialect"]["doubleQuote"], true);
+        assert_eq!(json["dialect"]["header"], true);
+        assert_eq!(json["dialect"]["lineTerminator"], "\n");
+        assert_eq!(json["dialect"]["headerRows"], serde_json::json!([1]));
+        assert_eq!(json["dialect"]["commentChar"], "");
+        assert_eq!(json["schema"]["fields"][0]["type"], "integer");
+        assert_eq!(json["schema"]["fields"][1]["type"], "number");
+        assert_eq!(json["schema"]["fields"][1]["bareNumber"], false);
+        assert_eq!(json["schema"]["fields"][2]["type"], "boolean");
+
+        let without_schema = serde_json::to_value(result.to_frictionless(false)).unwrap();
+        assert!(without_schema.get("schema").is_none());
+    }
+
+    #[test]
+    fn test_csvw_descriptor() {
+        let csv_data = "John\t25\t2023-01-15\nJane\t30\t2022-06-20\nBob\t35\t2021-03-10";
+        let result = Sniffer::new().detect_from_string(csv_data).unwrap();
+        let json = serde_json::to_value(result.to_csvw(true)).unwrap();
+
+        assert_eq!(json["@context"], "http://www.w3.org/ns/csvw");
+        assert_eq!(json["dialect"]["delimiter"], "\t");
+        assert_eq!(json["dialect"]["header"], false);
+        assert_eq!(json["dialect"]["headerRowCount"], 0);
+        assert_eq!(json["dialect"]["skipRows"], 0);
+        // Explicit, so rows starting with `#` are not read as comments
+        assert_eq!(json["dialect"]["commentPrefix"], "");
+        let columns = &json["tableSchema"]["columns"];
+        assert_eq!(columns[1]["name"], "column_2");
+        assert_eq!(columns[1]["datatype"], "integer");
+        assert_eq!(columns[2]["datatype"], "date");
+        assert!(columns[0].get("titles").is_none());
+
+        let result = Sniffer::new()
+            .detect_from_string("# export\nid,name\n10,Ann\n11,Bo\n")
+            .unwrap();
+        let json = serde_json::to_value(result.to_csvw(false)).unwrap();
+        assert_eq!(json["dialect"]["skipRows"], 1);
+
+        // Frictionless counts the preamble in the header row numbers
+        let json = serde_json::to_value(result.to_frictionless(false)).unwrap();
+        assert_eq!(json["dialect"]["headerRows"], serde_json::json!([2]));
+        assert_eq!(json["dialect"]["commentChar"], "#");
+    }
+}
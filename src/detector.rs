@@ -0,0 +1,225 @@
+//! Field type detectors used to classify CSV fields.
+//!
+//! A `Sniffer` checks its registered detectors in descending priority order and
+//! assigns a field the type of the first detector that matches it; fields no
+//! detector matches are `Text`.
+
+use crate::{DataType, classify_field};
+use regex::Regex;
+use std::sync::Arc;
+
+/// A pluggable field type detector
+///
+/// ```rust
+/// use csv_qsniffer::{DataType, Sniffer, TypeDetector};
+///
+/// struct Sku;
+///
+/// impl TypeDetector for Sku {
+///     fn name(&self) -> &str {
+///         "sku"
+///     }
+///
+///     fn matches(&self, field: &str) -> bool {
+///         field.len() == 8 && field.starts_with("SKU-")
+///     }
+/// }
+///
+/// let mut sniffer = Sniffer::new();
+/// sniffer.register_detector(Sku);
+///
+/// let result = sniffer
+///     .detect_from_string("item;qty\nSKU-0001;4\nSKU-0002;7")
+///     .unwrap();
+/// assert_eq!(result.columns[0].data_type, DataType::Custom("sku".to_string()));
+/// ```
+pub trait TypeDetector: Send + Sync {
+    /// Name of the detected type
+    fn name(&self) -> &str;
+
+    /// Whether a (trimmed, non-empty) field is of this type
+    fn matches(&self, field: &str) -> bool;
+
+    /// Weight applied to a column's uniformity when this is its dominant type
+    ///
+    /// Only used when the sniffer's `ScoringProfile` has no weight for `name`.
+    /// The built-in detectors' weights (1.0 to 1.2) seed the default profile,
+    /// alongside 0.8 for `Text`.
+    fn weight(&self) -> f64 {
+        1.0
+    }
+
+    /// Detectors with higher priority are checked first
+    ///
+    /// Built-in detectors use priorities 10 to 110, so the default of 0 checks
+    /// a custom detector only after every built-in type has been ruled out.
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    /// The `DataType` assigned to matching fields
+    fn data_type(&self) -> DataType {
+        DataType::Custom(self.name().to_string())
+    }
+
+    /// The built-in type whose pattern this detector checks, letting the
+    /// sniffer match a field against every built-in type in one pass
+    #[doc(hidden)]
+    fn builtin_type(&self) -> Option<&DataType> {
+        None
+    }
+}
+
+/// Detector for one of the built-in `DataType`s, backed by `classify_field`
+#[derive(Debug, Clone)]
+pub struct BuiltinDetector {
+    data_type: DataType,
+    name: &'static str,
+    weight: f64,
+    priority: i32,
+}
+
+impl TypeDetector for BuiltinDetector {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn matches(&self, field: &str) -> bool {
+        classify_field(field).contains(&self.data_type)
+    }
+
+    fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn data_type(&self) -> DataType {
+        self.data_type.clone()
+    }
+
+    fn builtin_type(&self) -> Option<&DataType> {
+        Some(&self.data_type)
+    }
+}
+
+/// Detector matching fields against a user-supplied regular expression
+#[derive(Debug, Clone)]
+pub struct RegexDetector {
+    name: String,
+    regex: Regex,
+    weight: f64,
+    priority: i32,
+}
+
+impl RegexDetector {
+    /// Create a detector for fields matching `pattern`, with weight 1.0 and priority 0
+    pub fn new(name: impl Into<String>, pattern: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            name: name.into(),
+            regex: Regex::new(pattern)?,
+            weight: 1.0,
+            priority: 0,
+        })
+    }
+
+    /// Set the scoring weight
+    #[must_use]
+    pub const fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Set the priority
+    #[must_use]
+    pub const fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+impl TypeDetector for RegexDetector {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn matches(&self, field: &str) -> bool {
+        self.regex.is_match(field)
+    }
+
+    fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+}
+
+/// Weight of columns whose dominant type is `Text`, which has no detector
+pub(crate) const TEXT_WEIGHT: f64 = 0.8;
+
+/// The built-in detectors, in the order they are checked
+///
+/// Their weights are the defaults of `ScoringProfile::type_weights`.
+#[must_use]
+pub fn default_detectors() -> Vec<Arc<dyn TypeDetector>> {
+    let builtin = |data_type, name, weight, priority| -> Arc<dyn TypeDetector> {
+        Arc::new(BuiltinDetector {
+            data_type,
+            name,
+            weight,
+            priority,
+        })
+    };
+
+    vec![
+        builtin(DataType::Boolean, "boolean", 1.0, 110),
+        builtin(DataType::Integer, "integer", 1.2, 100),
+        builtin(DataType::Float, "float", 1.2, 90),
+        builtin(DataType::DateTime, "date_time", 1.1, 80),
+        builtin(DataType::Date, "date", 1.1, 70),
+        builtin(DataType::Time, "time", 1.1, 60),
+        builtin(DataType::Email, "email", 1.1, 50),
+        builtin(DataType::Url, "url", 1.1, 40),
+        builtin(DataType::Phone, "phone", 1.0, 30),
+        builtin(DataType::Currency, "currency", 1.0, 20),
+        builtin(DataType::Percentage, "percentage", 1.0, 10),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sniffer;
+
+    #[test]
+    fn test_custom_detector_priority() {
+        let csv_data = "isin,qty\nUS0378331005,10\nUS5949181045,20\nGB0002634946,30";
+        let isin = RegexDetector::new("isin", r"^[A-Z]{2}[A-Z0-9]{9}\d$").unwrap();
+
+        let mut sniffer = Sniffer::new();
+        sniffer.register_detector(isin.clone());
+        let result = sniffer.detect_from_string(csv_data).unwrap();
+        assert_eq!(
+            result.columns[0].data_type,
+            DataType::Custom("isin".to_string())
+        );
+        assert_eq!(result.columns[1].data_type, DataType::Integer);
+
+        // A high-priority detector shadows the built-in types
+        let mut sniffer = Sniffer::new();
+        sniffer.register_detector(
+            RegexDetector::new("qty", r"^\d{2}$")
+                .unwrap()
+                .with_priority(200),
+        );
+        let result = sniffer.detect_from_string(csv_data).unwrap();
+        assert_eq!(
+            result.columns[1].data_type,
+            DataType::Custom("qty".to_string())
+        );
+    }
+}
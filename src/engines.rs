@@ -0,0 +1,308 @@
+//! Reader options for other CSV engines, rendered from a detected `Dialect`.
+//!
+//! Each renderer produces a ready-to-paste snippet in the target language, with
+//! special characters (tab, quotes, backslashes, control bytes) escaped using
+//! that language's literal syntax.
+
+use crate::Dialect;
+use std::fmt::Write;
+
+/// Quote a string as a Python literal, preferring double quotes
+fn python_str(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c if c.is_ascii_control() => {
+                let _ = write!(out, "\\x{:02x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn python_byte(b: Option<u8>) -> String {
+    b.map_or_else(
+        || "None".to_string(),
+        |b| python_str(&char::from(b).to_string()),
+    )
+}
+
+fn python_bool(b: bool) -> &'static str {
+    if b { "True" } else { "False" }
+}
+
+/// Quote a string as a single-quoted SQL literal, switching to a
+/// PostgreSQL-style `E'...'` literal when it contains control characters
+fn sql_str(s: &str) -> String {
+    if s.chars().any(|c| c.is_ascii_control()) {
+        let mut out = String::from("E'");
+        for c in s.chars() {
+            match c {
+                '\'' => out.push_str("\\'"),
+                '\\' => out.push_str("\\\\"),
+                '\t' => out.push_str("\\t"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                c if c.is_ascii_control() => {
+                    let _ = write!(out, "\\x{:02x}", c as u32);
+                }
+                c => out.push(c),
+            }
+        }
+        out.push('\'');
+        out
+    } else {
+        format!("'{}'", s.replace('\'', "''"))
+    }
+}
+
+/// Quote a single byte as a SQL literal, writing bytes outside ASCII as
+/// `E'\xHH'` escapes rather than as the Latin-1 character they would
+/// otherwise become
+fn sql_byte(b: u8) -> String {
+    if b.is_ascii() {
+        sql_str(&char::from(b).to_string())
+    } else {
+        format!("E'\\x{b:02x}'")
+    }
+}
+
+/// Quote a SQL identifier unless it is a plain lowercase name
+fn sql_ident(name: &str) -> String {
+    let plain = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if plain {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+impl Dialect {
+    /// Render a DuckDB `read_csv(...)` call for the file at `path`
+    ///
+    /// A tab delimiter uses DuckDB's backslash notation (`delim = '\t'`),
+    /// which `read_csv` interprets itself; other control bytes and bytes
+    /// outside ASCII are written as `E'\xHH'` escape strings.
+    #[must_use]
+    pub fn to_duckdb(&self, path: &str) -> String {
+        fn duckdb_byte(b: Option<u8>) -> String {
+            match b {
+                Some(b'\t') => "'\\t'".to_string(),
+                Some(b) => sql_byte(b),
+                None => "''".to_string(),
+            }
+        }
+
+        // DuckDB escapes quotes by doubling them unless told otherwise
+        let escape = self.escape.or(self.quote_char);
+        format!(
+            "read_csv({}, delim = {}, quote = {}, escape = {}, header = {})",
+            sql_str(path),
+            duckdb_byte(Some(self.delimiter)),
+            duckdb_byte(self.quote_char),
+            duckdb_byte(escape),
+            self.has_headers
+        )
+    }
+
+    /// Render a Polars `pl.read_csv(...)` call for the file at `path`
+    ///
+    /// Polars only reads doubled quotes, so a dialect with an escape character
+    /// gets a comment line warning that escaped quotes will not parse.
+    #[must_use]
+    pub fn to_polars(&self, path: &str) -> String {
+        let warning = self.escape.map_or_else(String::new, |escape| {
+            format!(
+                "# Polars has no escape character option; quotes escaped with {} will misparse\n",
+                python_byte(Some(escape))
+            )
+        });
+        format!(
+            "{warning}pl.read_csv({}, separator={}, quote_char={}, has_header={})",
+            python_str(path),
+            python_byte(Some(self.delimiter)),
+            python_byte(self.quote_char),
+            python_bool(self.has_headers)
+        )
+    }
+
+    /// Render a pandas `pd.read_csv(...)` call for the file at `path`
+    ///
+    /// Unquoted dialects pass `quoting=3`, the value of `csv.QUOTE_NONE`, so
+    /// the call needs no `import csv`.
+    #[must_use]
+    pub fn to_pandas(&self, path: &str) -> String {
+        let mut out = format!(
+            "pd.read_csv({}, sep={}, header={}",
+            python_str(path),
+            python_byte(Some(self.delimiter)),
+            if self.has_headers { "0" } else { "None" }
+        );
+        match self.quote_char {
+            Some(_) => {
+                let _ = write!(
+                    out,
+                    ", quotechar={}, doublequote={}",
+                    python_byte(self.quote_char),
+                    python_bool(self.escape.is_none())
+                );
+            }
+            None => out.push_str(", quoting=3"),
+        }
+        if self.escape.is_some() {
+            let _ = write!(out, ", escapechar={}", python_byte(self.escape));
+        }
+        out.push(')');
+        out
+    }
+
+    /// Render a PostgreSQL `COPY ... FROM ... WITH (FORMAT csv, ...)` statement
+    ///
+    /// PostgreSQL's CSV format always honors a quote character, so unquoted
+    /// dialects use `\x01`, which is not expected to appear in the data.
+    #[must_use]
+    pub fn to_postgres_copy(&self, table: &str, path: &str) -> String {
+        let mut out = format!(
+            "COPY {} FROM {} WITH (FORMAT csv, DELIMITER {}, QUOTE {}",
+            sql_ident(table),
+            sql_str(path),
+            sql_byte(self.delimiter),
+            sql_byte(self.quote_char.unwrap_or(0x01))
+        );
+        if let Some(escape) = self.escape {
+            let _ = write!(out, ", ESCAPE {}", sql_byte(escape));
+        }
+        let _ = write!(out, ", HEADER {})", self.has_headers);
+        out
+    }
+
+    /// Render a Python `csv.Dialect` subclass named `class_name`
+    #[must_use]
+    pub fn to_python_dialect(&self, class_name: &str) -> String {
+        let quoting = match (self.quote_char, self.quoting) {
+            (None, _) | (_, csv::QuoteStyle::Never) => "csv.QUOTE_NONE",
+            (_, csv::QuoteStyle::Always) => "csv.QUOTE_ALL",
+            (_, csv::QuoteStyle::NonNumeric) => "csv.QUOTE_NONNUMERIC",
+            _ => "csv.QUOTE_MINIMAL",
+        };
+        let lineterminator = match self.terminator {
+            csv::Terminator::Any(b) => char::from(b).to_string(),
+            _ => "\r\n".to_string(),
+        };
+
+        format!(
+            "import csv\n\n\
+             class {class_name}(csv.Dialect):\n    \
+             delimiter = {}\n    \
+             quotechar = {}\n    \
+             escapechar = {}\n    \
+             doublequote = {}\n    \
+             skipinitialspace = False\n    \
+             lineterminator = {}\n    \
+             quoting = {quoting}\n",
+            python_byte(Some(self.delimiter)),
+            python_byte(self.quote_char),
+            python_byte(self.escape),
+            python_bool(self.escape.is_none()),
+            python_str(&lineterminator),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Dialect;
+
+    fn tab_dialect() -> Dialect {
+        Dialect {
+            delimiter: b'\t',
+            quote_char: Some(b'\''),
+            escape: Some(b'\\'),
+            has_headers: true,
+            terminator: csv::Terminator::Any(b'\n'),
+            quoting: csv::QuoteStyle::Necessary,
+        }
+    }
+
+    #[test]
+    fn test_sql_renderers_escape_special_characters() {
+        let dialect = tab_dialect();
+
+        assert_eq!(
+            dialect.to_duckdb("it's.tsv"),
+            r"read_csv('it''s.tsv', delim = '\t', quote = '''', escape = '\', header = true)"
+        );
+        assert_eq!(
+            dialect.to_postgres_copy("Sales Data", "/tmp/sales.tsv"),
+            r#"COPY "Sales Data" FROM '/tmp/sales.tsv' WITH (FORMAT csv, DELIMITER E'\t', QUOTE '''', ESCAPE '\', HEADER true)"#
+        );
+
+        let unquoted = Dialect {
+            quote_char: None,
+            escape: None,
+            ..Dialect::default()
+        };
+        assert_eq!(
+            unquoted.to_postgres_copy("sales", "sales.csv"),
+            r"COPY sales FROM 'sales.csv' WITH (FORMAT csv, DELIMITER ',', QUOTE E'\x01', HEADER true)"
+        );
+
+        let latin1 = Dialect {
+            delimiter: 0xa7,
+            quote_char: Some(0x1f),
+            escape: None,
+            ..Dialect::default()
+        };
+        assert_eq!(
+            latin1.to_duckdb("data.csv"),
+            r"read_csv('data.csv', delim = E'\xa7', quote = E'\x1f', escape = E'\x1f', header = true)"
+        );
+        assert_eq!(
+            latin1.to_postgres_copy("t", "data.csv"),
+            r"COPY t FROM 'data.csv' WITH (FORMAT csv, DELIMITER E'\xa7', QUOTE E'\x1f', HEADER true)"
+        );
+    }
+
+    #[test]
+    fn test_python_renderers_escape_special_characters() {
+        let dialect = tab_dialect();
+
+        assert_eq!(
+            dialect.to_polars("data.tsv"),
+            "# Polars has no escape character option; quotes escaped with \"\\\\\" will misparse\n\
+             pl.read_csv(\"data.tsv\", separator=\"\\t\", quote_char=\"'\", has_header=True)"
+        );
+        assert_eq!(
+            dialect.to_pandas("data.tsv"),
+            r#"pd.read_csv("data.tsv", sep="\t", header=0, quotechar="'", doublequote=False, escapechar="\\")"#
+        );
+        let unquoted = Dialect {
+            quote_char: None,
+            ..Dialect::default()
+        };
+        assert_eq!(
+            unquoted.to_pandas("data.csv"),
+            r#"pd.read_csv("data.csv", sep=",", header=0, quoting=3)"#
+        );
+
+        let python = dialect.to_python_dialect("Sniffed");
+        assert!(python.contains("class Sniffed(csv.Dialect):\n"));
+        assert!(python.contains("    delimiter = \"\\t\"\n"));
+        assert!(python.contains("    escapechar = \"\\\\\"\n"));
+        assert!(python.contains("    lineterminator = \"\\n\"\n"));
+        assert!(python.contains("    quoting = csv.QUOTE_MINIMAL\n"));
+    }
+}
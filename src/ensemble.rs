@@ -0,0 +1,362 @@
+//! Ensemble detection combining several dialect scorers.
+//!
+//! Each scorer's scores are normalized across the candidate dialects, so that
+//! scorers with different scales can be combined, then aggregated by weighted
+//! vote or by rank.
+
+use crate::{
+    CandidateRejection, ConsistencyScorer, Dialect, DialectScorer, FrequencyScorer, Limit,
+    Rejection, SniffResult, Sniffer, SnifferError, TableUniformityScorer, no_valid_dialect,
+    split_preamble,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// How normalized scorer scores are combined into one score per candidate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggregation {
+    /// Weighted mean of min-max normalized scores
+    #[default]
+    WeightedVote,
+    /// Weighted Borda count: each scorer ranks the candidates, and a candidate
+    /// earns `(n - 1 - rank) / (n - 1)` points from it
+    Rank,
+}
+
+/// A weighted set of scorers used by `Sniffer::detect_ensemble`
+#[derive(Clone)]
+pub struct Ensemble {
+    members: Vec<(Arc<dyn DialectScorer>, f64)>,
+    /// How normalized scores are combined
+    pub aggregation: Aggregation,
+    /// Threshold for the weighted share of scorers whose own best dialect
+    /// matches the ensemble's pick; at or below it the result is flagged
+    /// ambiguous, so by default an even split between two scorers is
+    pub min_agreement: f64,
+}
+
+impl Default for Ensemble {
+    /// The TUM, `CleverCSV` and csv.Sniffer scorers with equal weights
+    fn default() -> Self {
+        Self::new()
+            .with_scorer(TableUniformityScorer, 1.0)
+            .with_scorer(ConsistencyScorer, 1.0)
+            .with_scorer(FrequencyScorer, 1.0)
+    }
+}
+
+impl Ensemble {
+    /// Create an empty ensemble using weighted voting and a 0.5 agreement threshold
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            members: Vec::new(),
+            aggregation: Aggregation::default(),
+            min_agreement: 0.5,
+        }
+    }
+
+    /// Add a scorer with the given (non-negative) weight
+    #[must_use]
+    pub fn with_scorer<S: DialectScorer + 'static>(mut self, scorer: S, weight: f64) -> Self {
+        self.members.push((Arc::new(scorer), weight.max(0.0)));
+        self
+    }
+
+    /// Set the aggregation method
+    #[must_use]
+    pub const fn with_aggregation(mut self, aggregation: Aggregation) -> Self {
+        self.aggregation = aggregation;
+        self
+    }
+
+    /// Set the agreement threshold at or below which results are ambiguous
+    #[must_use]
+    pub const fn with_min_agreement(mut self, min_agreement: f64) -> Self {
+        self.min_agreement = min_agreement;
+        self
+    }
+
+    /// Names of the member scorers, in insertion order
+    pub fn scorer_names(&self) -> impl Iterator<Item = &str> {
+        self.members.iter().map(|(scorer, _)| scorer.name())
+    }
+}
+
+/// One scorer's view of the candidates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScorerVote {
+    /// Name of the scorer
+    pub scorer: String,
+    /// Weight of the scorer in the ensemble
+    pub weight: f64,
+    /// The dialect this scorer ranks highest, if it accepted any candidate
+    pub dialect: Option<Dialect>,
+    /// The scorer's raw score for its own pick
+    pub score: f64,
+    /// Whether `dialect` is the ensemble's pick
+    pub agrees: bool,
+}
+
+/// Result of ensemble detection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnsembleResult {
+    /// The candidate with the highest combined score; `result.score` is that
+    /// combined score, between 0 and 1
+    pub result: SniffResult,
+    /// Per-scorer picks, in ensemble order
+    pub votes: Vec<ScorerVote>,
+    /// Weighted share of scorers whose own pick matches `result.dialect`
+    pub agreement: f64,
+    /// Whether `agreement` is at or below the ensemble's `min_agreement`
+    pub ambiguous: bool,
+}
+
+impl EnsembleResult {
+    /// Names of the scorers whose own pick matches the ensemble's
+    pub fn agreeing(&self) -> impl Iterator<Item = &str> {
+        self.votes
+            .iter()
+            .filter(|vote| vote.agrees)
+            .map(|vote| vote.scorer.as_str())
+    }
+}
+
+/// Min-max normalize scores to `[0, 1]`; rejected (non-finite) candidates get 0
+fn normalize(scores: &[f64]) -> Vec<f64> {
+    let finite = scores.iter().copied().filter(|s| s.is_finite());
+    let min = finite.clone().fold(f64::INFINITY, f64::min);
+    let max = finite.fold(f64::NEG_INFINITY, f64::max);
+
+    scores
+        .iter()
+        .map(|&score| {
+            if !score.is_finite() {
+                0.0
+            } else if max > min {
+                (score - min) / (max - min)
+            } else {
+                1.0
+            }
+        })
+        .collect()
+}
+
+/// Borda points in `[0, 1]`; rejected candidates get 0 and equal scores share
+/// the better rank
+fn rank_points(scores: &[f64]) -> Vec<f64> {
+    let accepted = scores.iter().filter(|s| s.is_finite()).count();
+    if accepted <= 1 {
+        return normalize(scores);
+    }
+
+    scores
+        .iter()
+        .map(|&score| {
+            if !score.is_finite() {
+                return 0.0;
+            }
+            let rank = scores.iter().filter(|&&other| other > score).count();
+            (accepted - 1).saturating_sub(rank) as f64 / (accepted - 1) as f64
+        })
+        .collect()
+}
+
+/// Index of the highest finite score, preferring the earliest candidate on ties
+fn best_index(scores: &[f64]) -> Option<usize> {
+    scores
+        .iter()
+        .enumerate()
+        .filter(|(_, score)| score.is_finite())
+        .fold(
+            None,
+            |best: Option<(usize, f64)>, (idx, &score)| match best {
+                Some((_, best_score)) if best_score >= score => best,
+                _ => Some((idx, score)),
+            },
+        )
+        .map(|(idx, _)| idx)
+}
+
+impl Sniffer {
+    /// Detect the dialect of string data by combining several scorers
+    ///
+    /// The sniffer's own scorer is not used. Returns `NoValidDialect` when the
+    /// ensemble is empty or no scorer accepts any candidate; candidates that
+    /// parsed but no scorer accepted are reported as rejected by the ensemble.
+    /// Limits apply as in `detect_from_string`: when the time budget expires,
+    /// the candidates parsed so far are combined and returned as the best
+    /// result of `SnifferError::LimitExceeded`.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(bytes = data.len()))
+    )]
+    pub fn detect_ensemble(
+        &self,
+        data: &str,
+        ensemble: &Ensemble,
+    ) -> Result<EnsembleResult, SnifferError> {
+        self.check_max_bytes(data)?;
+        let (preamble_rows, data) = split_preamble(data);
+        let (candidates, rejections) = self.candidate_tables(data, Some(Instant::now()));
+        let limit = rejections
+            .iter()
+            .find_map(|rejection| match rejection.reason {
+                Rejection::Limit(limit) => Some(limit),
+                _ => None,
+            });
+        let expired = rejections
+            .iter()
+            .find_map(|rejection| match rejection.reason {
+                Rejection::Limit(limit @ Limit::TimeBudget(_)) => Some(limit),
+                _ => None,
+            });
+        let total_weight: f64 = ensemble.members.iter().map(|(_, weight)| weight).sum();
+        let reject_all = |mut rejections: Vec<CandidateRejection>| {
+            rejections.extend(
+                candidates
+                    .iter()
+                    .map(|(dialect, table)| CandidateRejection {
+                        dialect: dialect.clone(),
+                        reason: table.rejection("ensemble"),
+                    }),
+            );
+            match limit {
+                Some(limit) => SnifferError::LimitExceeded { limit, best: None },
+                None => no_valid_dialect(data, rejections),
+            }
+        };
+        if candidates.is_empty() || total_weight <= 0.0 {
+            return Err(reject_all(rejections));
+        }
+
+        let mut combined = vec![0.0; candidates.len()];
+        let mut picks = Vec::with_capacity(ensemble.members.len());
+        for (scorer, weight) in &ensemble.members {
+            let scores: Vec<f64> = candidates
+                .iter()
+                .map(|(dialect, table)| scorer.score(self, data, dialect, table))
+                .collect();
+            let points = match ensemble.aggregation {
+                Aggregation::WeightedVote => normalize(&scores),
+                Aggregation::Rank => rank_points(&scores),
+            };
+            for (total, point) in combined.iter_mut().zip(points) {
+                *total += weight * point / total_weight;
+            }
+            picks.push(best_index(&scores).map(|idx| (idx, scores[idx])));
+        }
+
+        let Some(best) = best_index(&combined).filter(|_| picks.iter().any(Option::is_some)) else {
+            return Err(reject_all(rejections));
+        };
+
+        let votes: Vec<ScorerVote> = ensemble
+            .members
+            .iter()
+            .zip(picks)
+            .map(|((scorer, weight), pick)| ScorerVote {
+                scorer: scorer.name().to_string(),
+                weight: *weight,
+                dialect: pick.map(|(idx, _)| candidates[idx].0.clone()),
+                score: pick.map_or(f64::NEG_INFINITY, |(_, score)| score),
+                agrees: pick.is_some_and(|(idx, _)| idx == best),
+            })
+            .collect();
+        let agreement = votes
+            .iter()
+            .filter(|vote| vote.agrees)
+            .map(|vote| vote.weight)
+            .sum::<f64>()
+            / total_weight;
+
+        let (dialect, table) = &candidates[best];
+        #[cfg(feature = "tracing")]
+        tracing::debug!(%dialect, score = combined[best], agreement, "selected dialect");
+        let result = SniffResult {
+            preamble_rows,
+            ..self.build_result(dialect.clone(), table, combined[best])
+        };
+        if let Some(limit) = expired {
+            return Err(SnifferError::LimitExceeded {
+                limit,
+                best: Some(Box::new(result)),
+            });
+        }
+        Ok(EnsembleResult {
+            result,
+            votes,
+            agreement,
+            ambiguous: agreement <= ensemble.min_agreement,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CandidateTable;
+
+    /// Scorer that only accepts one delimiter
+    struct Only(u8);
+
+    impl DialectScorer for Only {
+        fn name(&self) -> &str {
+            "only"
+        }
+
+        fn score(&self, _: &Sniffer, _: &str, dialect: &Dialect, _: &CandidateTable) -> f64 {
+            if dialect.delimiter == self.0 {
+                1.0
+            } else {
+                f64::NEG_INFINITY
+            }
+        }
+    }
+
+    #[test]
+    fn test_ensemble_agreement() {
+        let csv_data = "name;age;city\nJohn;25;NYC\nJane;30;LA\nBob;35;SF";
+        let sniffer = Sniffer::new();
+
+        for aggregation in [Aggregation::WeightedVote, Aggregation::Rank] {
+            let ensemble = Ensemble::default().with_aggregation(aggregation);
+            let result = sniffer.detect_ensemble(csv_data, &ensemble).unwrap();
+
+            assert_eq!(result.result.dialect.delimiter, b';');
+            assert!(!result.ambiguous);
+            assert!((result.agreement - 1.0).abs() < 1e-9);
+            assert_eq!(
+                result.agreeing().collect::<Vec<_>>(),
+                ["tum", "clevercsv", "python"]
+            );
+        }
+    }
+
+    #[test]
+    fn test_ensemble_disagreement_is_ambiguous() {
+        let csv_data = "a,b;c\n1,2;3\n4,5;6\n7,8;9";
+        let ensemble = Ensemble::new()
+            .with_scorer(Only(b','), 1.0)
+            .with_scorer(Only(b';'), 1.0);
+        let result = Sniffer::new().detect_ensemble(csv_data, &ensemble).unwrap();
+
+        // Tied combined scores resolve to the earliest candidate
+        assert_eq!(result.result.dialect.delimiter, b',');
+        assert!((result.agreement - 0.5).abs() < 1e-9);
+        // An even split is at the default threshold
+        assert!(result.ambiguous);
+
+        let ensemble = ensemble.with_min_agreement(0.4);
+        let result = Sniffer::new().detect_ensemble(csv_data, &ensemble).unwrap();
+        assert!(!result.ambiguous);
+
+        assert!(
+            Sniffer::new()
+                .detect_ensemble(csv_data, &Ensemble::new())
+                .is_err()
+        );
+    }
+}
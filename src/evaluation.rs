@@ -0,0 +1,214 @@
+//! Accuracy evaluation against an annotated corpus (requires the `json` feature).
+//!
+//! Metrics follow the CSVsniffer paper: a file counts as predicted when the
+//! sniffer returns any dialect, so precision is measured over predictions and
+//! recall over all annotated files.
+
+use crate::{Dialect, LabeledSample, Sniffer};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Precision, recall and F1 for one dialect attribute
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AttributeMetrics {
+    /// Files where the attribute was detected correctly
+    pub correct: usize,
+    /// Files where a dialect was detected at all
+    pub predicted: usize,
+    /// Annotated files
+    pub total: usize,
+}
+
+impl AttributeMetrics {
+    fn record(&mut self, detected: Option<bool>) {
+        self.total += 1;
+        if let Some(correct) = detected {
+            self.predicted += 1;
+            self.correct += usize::from(correct);
+        }
+    }
+
+    /// Correct detections over all detections
+    #[must_use]
+    pub fn precision(&self) -> f64 {
+        ratio(self.correct, self.predicted)
+    }
+
+    /// Correct detections over all annotated files
+    #[must_use]
+    pub fn recall(&self) -> f64 {
+        ratio(self.correct, self.total)
+    }
+
+    /// Harmonic mean of precision and recall
+    #[must_use]
+    pub fn f1(&self) -> f64 {
+        let (precision, recall) = (self.precision(), self.recall());
+        if precision + recall == 0.0 {
+            0.0
+        } else {
+            2.0 * precision * recall / (precision + recall)
+        }
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+/// A file whose detected dialect differs from its annotation
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    pub path: PathBuf,
+    pub expected: Dialect,
+    /// `None` when detection failed
+    pub detected: Option<Dialect>,
+}
+
+/// Result of `Sniffer::evaluate`
+#[derive(Debug, Clone, Default)]
+pub struct Evaluation {
+    /// Files where delimiter, quote and escape characters all match
+    pub correct: usize,
+    /// Annotated files evaluated
+    pub total: usize,
+    pub delimiter: AttributeMetrics,
+    pub quote_char: AttributeMetrics,
+    pub escape: AttributeMetrics,
+    /// Number of files by (annotated delimiter, detected delimiter); the
+    /// detected delimiter is `None` when detection failed
+    pub delimiter_confusion: BTreeMap<(u8, Option<u8>), usize>,
+    /// Files that were not fully correct, in corpus order
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl Evaluation {
+    /// Fraction of files whose delimiter, quote and escape characters all match
+    #[must_use]
+    pub fn accuracy(&self) -> f64 {
+        ratio(self.correct, self.total)
+    }
+
+    /// Mean F1 over the delimiter, quote and escape attributes
+    #[must_use]
+    pub fn f1(&self) -> f64 {
+        (self.delimiter.f1() + self.quote_char.f1() + self.escape.f1()) / 3.0
+    }
+}
+
+impl Sniffer {
+    /// Run detection over an annotated corpus and score it against the annotations
+    ///
+    /// Each file goes through `Sniffer::detect`, so it is sampled to
+    /// `max_rows` lines with `\r\n` line endings normalized, and files shorter
+    /// than `min_rows` count as undetected.
+    #[must_use]
+    pub fn evaluate(&self, corpus: &[LabeledSample]) -> Evaluation {
+        let mut evaluation = Evaluation::default();
+
+        for sample in corpus {
+            let detected = self
+                .detect(sample.data.as_bytes())
+                .ok()
+                .map(|result| result.dialect);
+            let expected = &sample.dialect;
+
+            evaluation.total += 1;
+            evaluation
+                .delimiter
+                .record(detected.as_ref().map(|d| d.delimiter == expected.delimiter));
+            evaluation.quote_char.record(
+                detected
+                    .as_ref()
+                    .map(|d| d.quote_char == expected.quote_char),
+            );
+            evaluation
+                .escape
+                .record(detected.as_ref().map(|d| d.escape == expected.escape));
+            *evaluation
+                .delimiter_confusion
+                .entry((expected.delimiter, detected.as_ref().map(|d| d.delimiter)))
+                .or_insert(0) += 1;
+
+            if detected.as_ref().is_some_and(|d| sample.matches(d)) {
+                evaluation.correct += 1;
+            } else {
+                evaluation.mismatches.push(Mismatch {
+                    path: sample.path.clone(),
+                    expected: expected.clone(),
+                    detected,
+                });
+            }
+        }
+
+        evaluation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate() {
+        let sample = |data: &str, delimiter, quote_char| LabeledSample {
+            path: "sample.csv".into(),
+            data: data.to_string(),
+            dialect: Dialect {
+                delimiter,
+                quote_char,
+                ..Dialect::default()
+            },
+        };
+        let corpus = [
+            sample("name,age\nJo,25\nAl,30", b',', Some(b'"')),
+            sample("a;b;c\n1;2;3\n4;5;6", b';', Some(b'"')),
+            // Annotated wrongly on purpose: the sniffer will find ';'
+            sample("a;b;c\n7;8;9\n1;2;3", b'|', Some(b'"')),
+            sample("", b',', Some(b'"')),
+        ];
+        let evaluation = Sniffer::new().evaluate(&corpus);
+
+        assert_eq!(evaluation.total, 4);
+        assert_eq!(evaluation.correct, 2);
+        assert!((evaluation.accuracy() - 0.5).abs() < 1e-9);
+        assert_eq!(evaluation.delimiter.predicted, 3);
+        assert!((evaluation.delimiter.precision() - 2.0 / 3.0).abs() < 1e-9);
+        assert!((evaluation.delimiter.recall() - 0.5).abs() < 1e-9);
+        assert!((evaluation.quote_char.precision() - 1.0).abs() < 1e-9);
+        assert_eq!(evaluation.delimiter_confusion[&(b'|', Some(b';'))], 1);
+        assert_eq!(evaluation.delimiter_confusion[&(b',', None)], 1);
+        assert_eq!(evaluation.mismatches.len(), 2);
+    }
+
+    #[test]
+    fn test_evaluate_matches_detect() {
+        let sample = |data: &str| LabeledSample {
+            path: "sample.csv".into(),
+            data: data.to_string(),
+            // Matches nothing, so every detection is reported
+            dialect: Dialect {
+                delimiter: b'#',
+                ..Dialect::default()
+            },
+        };
+        let corpus = [
+            sample("id;note\r\n1;\"a\"\r\n2;\"b\"\r\n3;c\r\n"),
+            sample("a,b\n1,2\n"),
+        ];
+        let mut sniffer = Sniffer::new();
+        sniffer.min_rows = 3;
+        let evaluation = sniffer.evaluate(&corpus);
+
+        for (sample, mismatch) in corpus.iter().zip(&evaluation.mismatches) {
+            let detected = sniffer.detect(sample.data.as_bytes()).ok();
+            assert_eq!(mismatch.detected, detected.map(|result| result.dialect));
+        }
+        // Too short for `min_rows`
+        assert_eq!(evaluation.mismatches[1].detected, None);
+    }
+}
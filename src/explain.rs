@@ -0,0 +1,232 @@
+//! Per-candidate score breakdowns for debugging misdetections.
+//!
+//! `Sniffer::explain` scores every candidate dialect as `detect_from_string`
+//! does and records the Table Uniformity Method components behind each score:
+//! dominant type, uniformity and weight per column, the empty-field penalty
+//! and the number of dropped rows.
+
+use crate::{DataType, Dialect, Limit, Rejection, Scored, Sniffer, SnifferError, split_preamble};
+use serde::{Deserialize, Serialize};
+
+/// How one column contributes to a candidate's table uniformity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnBreakdown {
+    /// Header name, or `column_N` (1-based) when the data has no header row
+    pub name: String,
+    /// Most common non-empty type; `None` when every field is empty
+    pub data_type: Option<DataType>,
+    /// Fraction of the column's fields that have the dominant type
+    pub uniformity: f64,
+    /// Type weight applied to the column's uniformity
+    pub weight: f64,
+}
+
+/// Score breakdown of one candidate dialect
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CandidateExplanation {
+    pub dialect: Dialect,
+    /// Score under the sniffer's `DialectScorer`; `None` when rejected
+    pub score: Option<f64>,
+    /// Why the candidate was rejected, if it was
+    pub rejection: Option<Rejection>,
+    /// Number of rows kept for type analysis (excluding the header row)
+    pub num_rows: usize,
+    /// Number of rows dropped because their width was inconsistent
+    pub dropped_rows: usize,
+    /// Per-column breakdown of the kept rows
+    pub columns: Vec<ColumnBreakdown>,
+    /// Penalty for empty fields, subtracted from the TUM score
+    pub empty_penalty: f64,
+    /// Whether this candidate is the detected dialect
+    pub winner: bool,
+}
+
+/// Result of `Sniffer::explain`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Explanation {
+    /// Name of the sniffer's `DialectScorer`
+    pub scorer: String,
+    /// Every candidate dialect, in the order they were tried
+    pub candidates: Vec<CandidateExplanation>,
+}
+
+impl Explanation {
+    /// The detected candidate, if any was accepted
+    #[must_use]
+    pub fn winner(&self) -> Option<&CandidateExplanation> {
+        self.candidates.iter().find(|candidate| candidate.winner)
+    }
+}
+
+impl Sniffer {
+    /// Score every candidate dialect of string data and explain each score
+    ///
+    /// The winner is the candidate `detect_from_string` would return, and
+    /// candidates it prunes without parsing are reported as rejected. Limits
+    /// apply as in detection: input over the byte limit is refused, and once
+    /// the time budget expires the remaining candidates are reported as
+    /// rejected with `Limit::TimeBudget`.
+    /// The column breakdown and empty penalty are the TUM components and are
+    /// reported whichever scorer is in use.
+    pub fn explain(&self, data: &str) -> Result<Explanation, SnifferError> {
+        self.check_max_bytes(data)?;
+        let (_, data) = split_preamble(data);
+        let evaluated = self.evaluate_candidates(data);
+
+        let mut candidates: Vec<_> = evaluated
+            .candidates
+            .into_iter()
+            .map(|(dialect, outcome)| self.explain_candidate(dialect, outcome))
+            .collect();
+        if let Some(idx) = evaluated.winner {
+            candidates[idx].winner = true;
+        }
+        Ok(Explanation {
+            scorer: self.scorer.name().to_string(),
+            candidates,
+        })
+    }
+
+    /// Break down the score of one evaluated candidate
+    fn explain_candidate(&self, dialect: Dialect, outcome: Scored) -> CandidateExplanation {
+        let (table, score) = match outcome {
+            Scored::Parsed(table, score) => (table, score),
+            Scored::Rejected(rejection) | Scored::Pruned(rejection) => {
+                return Self::rejected_candidate(dialect, rejection);
+            }
+            Scored::Expired(budget) => {
+                return Self::rejected_candidate(
+                    dialect,
+                    Rejection::Limit(Limit::TimeBudget(budget)),
+                );
+            }
+        };
+
+        let rejection = (score == f64::NEG_INFINITY).then(|| table.rejection(self.scorer.name()));
+        let columns = table
+            .column_type_counts
+            .iter()
+            .enumerate()
+            .map(|(col_idx, type_counts)| {
+                let total: usize = type_counts.values().sum();
+                let dominant = Self::dominant_type(type_counts);
+                let (data_type, count) = dominant.clone().unwrap_or((DataType::Text, 0));
+                ColumnBreakdown {
+                    name: table.column_name(col_idx),
+                    data_type: dominant.map(|(data_type, _)| data_type),
+                    uniformity: if total == 0 {
+                        0.0
+                    } else {
+                        count as f64 / total as f64
+                    },
+                    weight: self.type_weight(&data_type),
+                }
+            })
+            .collect();
+
+        CandidateExplanation {
+            score: rejection.is_none().then_some(score),
+            rejection,
+            num_rows: table.num_rows,
+            dropped_rows: table.dropped_rows,
+            columns,
+            empty_penalty: self.calculate_empty_penalty(&table),
+            winner: false,
+            dialect,
+        }
+    }
+
+    /// Explain a candidate rejected before it was scored
+    fn rejected_candidate(dialect: Dialect, rejection: Rejection) -> CandidateExplanation {
+        // Pruned candidates still know how many rows were ragged
+        let dropped_rows = match rejection {
+            Rejection::InconsistentRows { dropped_rows, .. } => dropped_rows,
+            _ => 0,
+        };
+        CandidateExplanation {
+            dialect,
+            score: None,
+            rejection: Some(rejection),
+            num_rows: 0,
+            dropped_rows,
+            columns: Vec::new(),
+            empty_penalty: 0.0,
+            winner: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_matches_detection() {
+        let sniffer = Sniffer::new();
+        let data = "name,age,score\nJohn,25,1.5\nJane,30,2.5\nBob,,3.5";
+        let explanation = sniffer.explain(data).unwrap();
+        let detected = sniffer.detect_from_string(data).unwrap();
+
+        assert_eq!(explanation.scorer, "tum");
+        assert_eq!(explanation.candidates.len(), 14);
+        let winner = explanation.winner().unwrap();
+        assert_eq!(winner.dialect, detected.dialect);
+        assert_eq!(winner.score, Some(detected.score));
+
+        // The breakdown reproduces the TUM score
+        let uniformity = winner
+            .columns
+            .iter()
+            .map(|column| column.uniformity * column.weight)
+            .sum::<f64>()
+            / winner.columns.len() as f64;
+        let expected = uniformity.mul_add(
+            sniffer.scoring_profile.row_consistency_bonus,
+            -winner.empty_penalty,
+        );
+        assert!((expected - detected.score).abs() < 1e-9);
+        assert_eq!(winner.columns[2].data_type, Some(DataType::Float));
+        assert!(winner.empty_penalty > 0.0);
+    }
+
+    #[test]
+    fn test_explain_reports_rejected_candidates() {
+        let explanation = Sniffer::new()
+            .explain("name;city\nJo;Paris\nAl;Rome, Italy\nBo;Oslo")
+            .unwrap();
+        let comma = explanation
+            .candidates
+            .iter()
+            .find(|candidate| candidate.dialect.delimiter == b',')
+            .unwrap();
+
+        assert_eq!(comma.score, None);
+        assert_eq!(comma.dropped_rows, 1);
+        assert!(matches!(
+            comma.rejection,
+            Some(Rejection::InconsistentRows { line: 3, .. })
+        ));
+        assert_eq!(explanation.winner().unwrap().dialect.delimiter, b';');
+    }
+
+    #[test]
+    fn test_explain_picks_the_detected_winner() {
+        let sniffer = Sniffer::new();
+        // A single column is only scored in the deferred round, and quotes
+        // present in the data break the tie between quote characters
+        for data in [
+            "name\nAnn\nBo\nCy\n",
+            "id,note\n1,'a, b'\n2,'c, d'\n3,'e, f'\n",
+        ] {
+            let explanation = sniffer.explain(data).unwrap();
+            let detected = sniffer.detect_from_string(data).unwrap();
+            let winner = explanation.winner().unwrap();
+            assert_eq!(winner.dialect, detected.dialect, "{data:?}");
+            assert_eq!(winner.score, Some(detected.score));
+            assert_eq!(
+                explanation.candidates.iter().filter(|c| c.winner).count(),
+                1
+            );
+        }
+    }
+}
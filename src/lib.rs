@@ -19,28 +19,121 @@
 //! assert_eq!(dialect.quote_char, Some(b'"'));
 //! ```
 
-use csv::{ReaderBuilder, StringRecord};
-use regex::Regex;
-use std::collections::HashMap;
-use std::io::{BufRead, Cursor};
-use std::sync::OnceLock;
+use csv::ReaderBuilder;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::{BufRead, Read};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
+use cache::SniffCache;
+use prefilter::Prefilter;
+use records::RecordReader;
+
+#[cfg(feature = "arrow")]
+mod arrow;
+mod cache;
+mod classify;
+#[cfg(feature = "json")]
+mod corpus;
+mod ddl;
+mod descriptor;
+mod detector;
+mod engines;
+mod ensemble;
+#[cfg(feature = "json")]
+mod evaluation;
+mod explain;
+mod limits;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod prefilter;
+mod records;
+mod rejection;
+mod scorer;
+mod scoring;
+mod serde_fmt;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "train")]
+mod training;
+
+#[cfg(feature = "arrow")]
+pub use arrow::ArrowCsvOptions;
+pub use classify::{TypeSet, classify_field};
+#[cfg(feature = "json")]
+pub use corpus::{LabeledSample, load_annotated_corpus, load_corpus};
+pub use ddl::{SqlDialect, sql_table_name};
+pub use descriptor::{
+    CsvwColumn, CsvwDescriptor, CsvwDialect, CsvwTableSchema, FrictionlessDescriptor,
+    FrictionlessDialect, SchemaField, TableSchema,
+};
+pub use detector::{BuiltinDetector, RegexDetector, TypeDetector, default_detectors};
+pub use ensemble::{Aggregation, Ensemble, EnsembleResult, ScorerVote};
+#[cfg(feature = "json")]
+pub use evaluation::{AttributeMetrics, Evaluation, Mismatch};
+pub use explain::{CandidateExplanation, ColumnBreakdown, Explanation};
+pub use limits::{Limit, Limits};
+#[cfg(feature = "mmap")]
+pub use mmap::FileSniffResult;
+pub use rejection::{CandidateRejection, Rejection};
+pub use scorer::{ConsistencyScorer, DialectScorer, FrequencyScorer, TableUniformityScorer};
+pub use scoring::ScoringProfile;
+#[cfg(feature = "train")]
+pub use training::{TrainingOptions, TrainingReport};
+
 /// Errors that can occur during CSV dialect detection
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum SnifferError {
     #[error("CSV parsing error: {0}")]
     CsvError(#[from] csv::Error),
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
-    #[error("No valid dialect found")]
-    NoValidDialect,
-    #[error("Invalid input data")]
-    InvalidInput,
+    #[error(
+        "No valid dialect found in {rows_read} rows ({bytes_read} bytes): {} candidate(s) rejected",
+        rejections.len()
+    )]
+    NoValidDialect {
+        rows_read: usize,
+        bytes_read: usize,
+        /// Every candidate dialect tried, with the reason it was rejected
+        rejections: Vec<CandidateRejection>,
+    },
+    #[error(
+        "Not enough data: read {rows_read} rows ({bytes_read} bytes), at least {min_rows} required"
+    )]
+    InvalidInput {
+        rows_read: usize,
+        bytes_read: usize,
+        min_rows: usize,
+    },
+    #[error("Invalid scoring profile: {0}")]
+    InvalidProfile(String),
+    #[error("Invalid dialect annotation {}: {message}", path.display())]
+    InvalidAnnotation { path: PathBuf, message: String },
+    #[error("Resource limit exceeded: {limit}")]
+    LimitExceeded {
+        limit: Limit,
+        /// Best result among the candidates scored before the time budget expired
+        best: Option<Box<SniffResult>>,
+    },
+    #[error("Failed to deserialize record at line {line} (detected dialect: {dialect}): {source}")]
+    Deserialize {
+        dialect: Dialect,
+        line: u64,
+        source: csv::Error,
+    },
 }
 
 /// Data types that can be detected in CSV fields
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum DataType {
     Integer,
     Float,
@@ -55,90 +148,335 @@ pub enum DataType {
     Percentage,
     Text,
     Empty,
+    /// A type recognized by a user-registered `TypeDetector`
+    Custom(String),
+}
+
+impl DataType {
+    /// Name of the type, as used by detectors and scoring profiles
+    #[must_use]
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::Boolean => "boolean",
+            Self::Date => "date",
+            Self::Time => "time",
+            Self::DateTime => "date_time",
+            Self::Email => "email",
+            Self::Url => "url",
+            Self::Phone => "phone",
+            Self::Currency => "currency",
+            Self::Percentage => "percentage",
+            Self::Text => "text",
+            Self::Empty => "empty",
+            Self::Custom(name) => name,
+        }
+    }
+}
+
+/// Result of dialect detection, including the inferred column layout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SniffResult {
+    /// The detected dialect
+    pub dialect: Dialect,
+    /// Score of the detected dialect under the sniffer's `DialectScorer`
+    pub score: f64,
+    /// Number of data rows analyzed (excluding the header row)
+    pub num_rows: usize,
+    /// Per-column profiles, in column order
+    pub columns: Vec<ColumnProfile>,
+    /// Lines starting with `#` before the table, which were skipped
+    #[serde(default)]
+    pub preamble_rows: usize,
+    /// Candidates discarded by the prefilter without being parsed, with the
+    /// reason each could not have been selected
+    #[serde(default)]
+    pub pruned: Vec<CandidateRejection>,
+}
+
+/// Inferred profile of a single column
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnProfile {
+    /// Header name, or `column_N` (1-based) when the data has no header row
+    pub name: String,
+    /// Most common non-empty data type in the column
+    pub data_type: DataType,
+    /// Number of empty fields in the column
+    pub empty_count: usize,
+    /// Most digits before the decimal point among values of the dominant
+    /// numeric type
+    #[serde(default)]
+    pub max_integer_digits: usize,
+    /// Most digits after the decimal point among values of the dominant
+    /// numeric type, or of fractional seconds for date-times
+    #[serde(default)]
+    pub max_fraction_digits: usize,
+    /// Every value of the dominant numeric type had `max_fraction_digits`
+    /// fraction digits and no exponent
+    #[serde(default)]
+    pub fixed_scale: bool,
+    /// Every value of the dominant type was in its standard form:
+    /// `true`/`false` booleans, ISO 8601 dates, and ISO 8601 date-times on a
+    /// 24-hour clock
+    #[serde(default)]
+    pub standard_form: bool,
 }
 
-/// Table structure for uniformity analysis
-#[derive(Debug)]
-struct Table {
-    records: Vec<StringRecord>,
-    column_types: Vec<Vec<DataType>>,
+/// Sample data parsed with one candidate dialect, as seen by a `DialectScorer`
+///
+/// Rows whose width differs from the header (or the first record, when there
+/// is no header) are dropped from the typed columns but still counted in
+/// `row_length_counts`.
+#[derive(Debug, Clone)]
+pub struct CandidateTable {
+    headers: Option<Vec<String>>,
+    column_type_counts: Vec<HashMap<DataType, usize>>,
+    /// Digit counts and formats per column and type
+    value_shapes: Vec<HashMap<DataType, ValueShape>>,
+    row_length_counts: BTreeMap<usize, usize>,
     num_columns: usize,
     num_rows: usize,
+    dropped_rows: usize,
+    /// Line and width of the first dropped row
+    first_dropped: Option<(u64, usize)>,
 }
 
-/// Global static regex cache - compiled once and reused across all Sniffer instances
-static TYPE_REGEXES: OnceLock<HashMap<DataType, Regex>> = OnceLock::new();
+/// Digit counts and formats shared by the values of one type in a column
+#[derive(Debug, Clone, Copy, Default)]
+struct ValueShape {
+    integer_digits: usize,
+    fraction_digits: usize,
+    /// Every value had `fraction_digits` fraction digits and no exponent
+    fixed_scale: bool,
+    /// Every value was in its type's standard form
+    standard_form: bool,
+}
 
-/// Initialize the global regex cache
-fn get_type_regexes() -> &'static HashMap<DataType, Regex> {
-    TYPE_REGEXES.get_or_init(|| {
-        let mut type_regexes = HashMap::new();
+impl ValueShape {
+    /// Shape of one field, for types with digits or a standard form
+    fn of(field: &str, data_type: &DataType) -> Option<Self> {
+        let count = |s: &str| s.bytes().filter(u8::is_ascii_digit).count();
+
+        let (integer_digits, fraction_digits, fixed_scale) = match data_type {
+            DataType::Integer | DataType::Float | DataType::Currency | DataType::Percentage => {
+                let mut parts = field.split(['e', 'E']);
+                let mantissa = parts.next().unwrap_or_default();
+                let exponent = parts.next().is_some();
+                match mantissa.split_once('.') {
+                    Some((int_part, frac_part)) => (count(int_part), count(frac_part), !exponent),
+                    None => (count(mantissa), 0, !exponent),
+                }
+            }
+            DataType::DateTime => {
+                let fraction = field.rsplit_once('.').map_or("", |(_, frac)| frac);
+                let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+                (0, digits, false)
+            }
+            DataType::Boolean | DataType::Date => (0, 0, false),
+            _ => return None,
+        };
+        Some(Self {
+            integer_digits,
+            fraction_digits,
+            fixed_scale,
+            standard_form: classify::is_standard_form(field, data_type),
+        })
+    }
 
-        // Integer pattern
-        type_regexes.insert(DataType::Integer, Regex::new(r"^[+-]?\d+$").unwrap());
+    fn merge(&mut self, other: Self) {
+        self.fixed_scale &= other.fixed_scale && other.fraction_digits == self.fraction_digits;
+        self.standard_form &= other.standard_form;
+        self.integer_digits = self.integer_digits.max(other.integer_digits);
+        self.fraction_digits = self.fraction_digits.max(other.fraction_digits);
+    }
+}
 
-        // Float pattern
-        type_regexes.insert(
-            DataType::Float,
-            Regex::new(r"^[+-]?(\d+\.?\d*|\.\d+)([eE][+-]?\d+)?$").unwrap(),
-        );
+impl CandidateTable {
+    /// Number of rows kept for type analysis (excluding the header row)
+    #[must_use]
+    pub const fn num_rows(&self) -> usize {
+        self.num_rows
+    }
 
-        // Boolean pattern
-        type_regexes.insert(
-            DataType::Boolean,
-            Regex::new(r"^(?i)(true|false|yes|no|y|n|1|0|on|off)$").unwrap(),
-        );
+    /// Number of columns in the kept rows
+    #[must_use]
+    pub const fn num_columns(&self) -> usize {
+        self.num_columns
+    }
 
-        // Date patterns (various formats)
-        type_regexes.insert(
-            DataType::Date,
-            Regex::new(r"^\d{1,4}[-/]\d{1,2}[-/]\d{1,4}$").unwrap(),
-        );
+    /// Number of rows dropped because their width was inconsistent
+    #[must_use]
+    pub const fn dropped_rows(&self) -> usize {
+        self.dropped_rows
+    }
 
-        // Time pattern
-        type_regexes.insert(
-            DataType::Time,
-            Regex::new(r"^\d{1,2}:\d{2}(:\d{2})?(\s?(AM|PM))?$").unwrap(),
-        );
+    /// Number of rows (including the header row) by field count
+    #[must_use]
+    pub const fn row_length_counts(&self) -> &BTreeMap<usize, usize> {
+        &self.row_length_counts
+    }
 
-        // DateTime pattern
-        type_regexes.insert(
-            DataType::DateTime,
-            Regex::new(r"^\d{1,4}[-/]\d{1,2}[-/]\d{1,4}\s+\d{1,2}:\d{2}(:\d{2})?").unwrap(),
-        );
+    /// Number of fields of each data type, per column of the kept rows
+    #[must_use]
+    pub fn column_type_counts(&self) -> &[HashMap<DataType, usize>] {
+        &self.column_type_counts
+    }
 
-        // Email pattern
-        type_regexes.insert(
-            DataType::Email,
-            Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap(),
-        );
+    /// Header name of a column, or `column_N` (1-based) without a header row
+    pub(crate) fn column_name(&self, col_idx: usize) -> String {
+        self.headers
+            .as_ref()
+            .and_then(|headers| headers.get(col_idx))
+            .map_or_else(|| format!("column_{}", col_idx + 1), String::clone)
+    }
 
-        // URL pattern
-        type_regexes.insert(
-            DataType::Url,
-            Regex::new(r"^https?://[^\s/$.?#].[^\s]*$").unwrap(),
-        );
+    /// Why a scorer that gave this table no finite score rejected it
+    pub(crate) fn rejection(&self, scorer: &str) -> Rejection {
+        match self.first_dropped {
+            Some((line, fields)) => Rejection::InconsistentRows {
+                dropped_rows: self.dropped_rows,
+                line,
+                fields,
+                expected: self.num_columns,
+            },
+            None => Rejection::Scorer(scorer.to_string()),
+        }
+    }
 
-        // Phone pattern (basic)
-        type_regexes.insert(
-            DataType::Phone,
-            Regex::new(r"^[\+]?[\d\s\-\(\)\.]{7,15}$").unwrap(),
-        );
+    /// Total number of empty fields in the kept rows
+    #[must_use]
+    pub fn empty_count(&self) -> usize {
+        self.column_type_counts
+            .iter()
+            .filter_map(|counts| counts.get(&DataType::Empty))
+            .sum()
+    }
+}
 
-        // Currency pattern
-        type_regexes.insert(
-            DataType::Currency,
-            Regex::new(r"^[\$£€¥]?[+-]?\d{1,3}(,\d{3})*(\.\d{2})?[\$£€¥]?$").unwrap(),
-        );
+/// `SnifferError::NoValidDialect` for the sampled `data`
+pub(crate) fn no_valid_dialect(data: &str, rejections: Vec<CandidateRejection>) -> SnifferError {
+    SnifferError::NoValidDialect {
+        rows_read: data.lines().count(),
+        bytes_read: data.len(),
+        rejections,
+    }
+}
 
-        // Percentage pattern
-        type_regexes.insert(
-            DataType::Percentage,
-            Regex::new(r"^[+-]?\d+(\.\d+)?%$").unwrap(),
-        );
+/// Split the preamble off `data`: the leading lines starting with `#`, as
+/// long as some other line follows them
+///
+/// A `#` line with as many commas, semicolons, tabs or pipes as the first
+/// line after the `#` lines has that row's shape, so it is a header row
+/// (`#id,name`, VCF's `#CHROM`) and ends the preamble.
+///
+/// Returns the number of preamble lines and the data after them.
+pub(crate) fn split_preamble(data: &str) -> (usize, &str) {
+    let delimiter_counts =
+        |line: &str| [b',', b';', b'\t', b'|'].map(|d| line.bytes().filter(|&b| b == d).count());
+    let first_row = data
+        .lines()
+        .find(|line| !line.starts_with('#'))
+        .unwrap_or_default();
+    let row_counts = delimiter_counts(first_row);
+    let is_header = |line: &str| {
+        delimiter_counts(line)
+            .iter()
+            .zip(&row_counts)
+            .any(|(&count, &row_count)| count > 0 && count == row_count)
+    };
+
+    let mut rows = 0;
+    let mut rest = data;
+    while rest.starts_with('#')
+        && let Some((line, next)) = rest.split_once('\n')
+        && !next.is_empty()
+        && !is_header(line)
+    {
+        rows += 1;
+        rest = next;
+    }
+    (rows, rest)
+}
 
-        type_regexes
-    })
+/// Whether `dialect`'s quote character occurs in `data`, which breaks ties
+/// between candidates that parse `data` into the same table
+pub(crate) fn quote_occurs(data: &str, dialect: &Dialect) -> bool {
+    dialect
+        .quote_char
+        .is_some_and(|quote| data.as_bytes().contains(&quote))
+}
+
+/// Span carrying a candidate dialect's parameters as fields
+#[cfg(feature = "tracing")]
+pub(crate) fn dialect_span(dialect: &Dialect) -> tracing::Span {
+    tracing::debug_span!(
+        "candidate",
+        delimiter = %char::from(dialect.delimiter).escape_default(),
+        quote = ?dialect.quote_char.map(char::from),
+        has_headers = dialect.has_headers
+    )
+}
+
+/// Outcome of evaluating one candidate dialect
+pub(crate) enum Scored {
+    /// Parsed and scored; a score of `f64::NEG_INFINITY` means the scorer
+    /// rejected the table
+    Parsed(CandidateTable, f64),
+    /// Failed to parse
+    Rejected(Rejection),
+    /// Discarded by the prefilter without being parsed
+    Pruned(Rejection),
+    /// Skipped because the time budget expired
+    Expired(Duration),
+}
+
+impl Scored {
+    /// The score of a candidate the scorer accepted
+    pub(crate) fn score(&self) -> Option<f64> {
+        match *self {
+            Self::Parsed(_, score) if score != f64::NEG_INFINITY => Some(score),
+            _ => None,
+        }
+    }
+
+    /// Whether this outcome ends the search: the candidate was accepted or
+    /// hit a limit
+    fn is_final(&self) -> bool {
+        matches!(self, Self::Rejected(Rejection::Limit(_)) | Self::Expired(_))
+            || self.score().is_some()
+    }
+}
+
+/// Every candidate dialect of one sniff and what became of it
+pub(crate) struct Candidates {
+    /// Candidates in the order they were generated
+    pub(crate) candidates: Vec<(Dialect, Scored)>,
+    /// Index of the detected candidate, if any was accepted
+    pub(crate) winner: Option<usize>,
+}
+
+/// Index of the winning candidate given each candidate's score (`None` when
+/// rejected) and whether its quote character occurs in the data
+///
+/// The highest score wins. Ties go to the first candidate whose quote
+/// character occurs, then to the earliest candidate.
+pub(crate) fn best_candidate(
+    scores: impl IntoIterator<Item = Option<f64>>,
+    quoted: impl Fn(usize) -> bool,
+) -> Option<usize> {
+    let mut best: Option<(usize, f64)> = None;
+    for (idx, score) in scores.into_iter().enumerate() {
+        let Some(score) = score else {
+            continue;
+        };
+        if best.is_none_or(|(best_idx, best_score)| {
+            score > best_score || (score == best_score && quoted(idx) && !quoted(best_idx))
+        }) {
+            best = Some((idx, score));
+        }
+    }
+    best.map(|(idx, _)| idx)
 }
 
 /// Main CSV dialect detection engine
@@ -147,6 +485,14 @@ pub struct Sniffer {
     pub max_rows: usize,
     /// Minimum number of rows required for analysis
     pub min_rows: usize,
+    /// Weights and penalty factors used to score candidate dialects
+    pub scoring_profile: ScoringProfile,
+    /// Resource limits for untrusted input
+    pub limits: Limits,
+    /// Field type detectors, sorted by descending priority
+    detectors: Vec<Arc<dyn TypeDetector>>,
+    /// Scoring algorithm used to rank candidate dialects
+    scorer: Arc<dyn DialectScorer>,
 }
 
 impl Default for Sniffer {
@@ -162,48 +508,491 @@ impl Sniffer {
         Self {
             max_rows: 1000,
             min_rows: 2,
+            scoring_profile: ScoringProfile::default(),
+            limits: Limits::default(),
+            detectors: default_detectors(),
+            scorer: Arc::new(TableUniformityScorer),
         }
     }
 
+    /// Replace the scoring algorithm used to rank candidate dialects
+    pub fn set_scorer<S: DialectScorer + 'static>(&mut self, scorer: S) -> &mut Self {
+        self.scorer = Arc::new(scorer);
+        self
+    }
+
+    /// The scoring algorithm used to rank candidate dialects
+    #[must_use]
+    pub fn scorer(&self) -> &dyn DialectScorer {
+        self.scorer.as_ref()
+    }
+
+    /// Register an additional field type detector
+    ///
+    /// Detectors are checked in descending priority order; detectors with equal
+    /// priority are checked in registration order.
+    pub fn register_detector<D: TypeDetector + 'static>(&mut self, detector: D) -> &mut Self {
+        self.detectors.push(Arc::new(detector));
+        self.detectors
+            .sort_by_key(|detector| std::cmp::Reverse(detector.priority()));
+        self
+    }
+
+    /// The registered field type detectors, in the order they are checked
+    #[must_use]
+    pub fn detectors(&self) -> &[Arc<dyn TypeDetector>] {
+        &self.detectors
+    }
+
+    /// Remove all field type detectors, including the built-in ones
+    pub fn clear_detectors(&mut self) -> &mut Self {
+        self.detectors.clear();
+        self
+    }
+
     /// Detect the most likely CSV dialect for the given data
     pub fn sniff<R: BufRead>(&self, reader: R) -> Result<Dialect, SnifferError> {
-        // Read sample data
+        self.detect(reader).map(|result| result.dialect)
+    }
+
+    /// Detect dialect from string data
+    pub fn sniff_from_string(&self, data: &str) -> Result<Dialect, SnifferError> {
+        self.detect_from_string(data).map(|result| result.dialect)
+    }
+
+    /// Detect the most likely CSV dialect for the given data, along with its
+    /// score and column profiles
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(max_rows = self.max_rows))
+    )]
+    pub fn detect<R: BufRead>(&self, reader: R) -> Result<SniffResult, SnifferError> {
+        let sample = self.read_sample(reader)?;
+        self.detect_from_string(&sample)
+    }
+
+    /// Read the first `max_rows` lines of `reader` as the sample `detect`
+    /// sniffs, keeping their line endings (so CRLF files are detected as
+    /// such) and ending the last line with `\n`
+    ///
+    /// Fails when fewer than `min_rows` lines could be read.
+    pub(crate) fn read_sample<R: BufRead>(&self, reader: R) -> Result<String, SnifferError> {
+        // Read sample data, never buffering more than one byte past `max_bytes`
+        let mut reader = reader.take(self.limits.max_bytes.map_or(u64::MAX, |max| max as u64 + 1));
         let mut sample_data = String::new();
         let mut lines_read = 0;
+        let mut bytes_read = 0;
+        let mut line = Vec::new();
 
-        for line in reader.lines() {
-            if lines_read >= self.max_rows {
+        while lines_read < self.max_rows {
+            line.clear();
+            let read = reader.read_until(b'\n', &mut line)?;
+            if read == 0 {
                 break;
             }
-            sample_data.push_str(&line?);
-            sample_data.push('\n');
+            bytes_read += read;
+            if let Some(max_bytes) = self.limits.max_bytes
+                && reader.limit() == 0
+            {
+                return Err(SnifferError::LimitExceeded {
+                    limit: Limit::Bytes(max_bytes),
+                    best: None,
+                });
+            }
+
+            let line = std::str::from_utf8(&line)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            sample_data.push_str(line);
+            if !line.ends_with('\n') {
+                sample_data.push('\n');
+            }
             lines_read += 1;
         }
 
+        #[cfg(feature = "tracing")]
+        tracing::debug!(rows = lines_read, bytes = bytes_read, "sampled input");
+
         if lines_read < self.min_rows {
-            return Err(SnifferError::InvalidInput);
+            return Err(SnifferError::InvalidInput {
+                rows_read: lines_read,
+                bytes_read,
+                min_rows: self.min_rows,
+            });
         }
 
-        self.sniff_from_string(&sample_data)
+        Ok(sample_data)
     }
 
-    /// Detect dialect from string data
-    pub fn sniff_from_string(&self, data: &str) -> Result<Dialect, SnifferError> {
-        let potential_dialects = self.generate_potential_dialects(data);
-        let mut best_dialect = None;
-        let mut best_score = f64::NEG_INFINITY;
-
-        for dialect in potential_dialects {
-            if let Ok(table) = self.parse_with_dialect(data, &dialect) {
-                let score = self.calculate_table_uniformity(&table);
-                if score > best_score {
-                    best_score = score;
-                    best_dialect = Some(dialect);
+    /// Detect dialect, score and column profiles from string data
+    ///
+    /// Leading lines starting with `#` are skipped as a preamble and counted
+    /// in `SniffResult::preamble_rows`.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(bytes = data.len(), scorer = self.scorer.name())
+        )
+    )]
+    pub fn detect_from_string(&self, data: &str) -> Result<SniffResult, SnifferError> {
+        self.check_max_bytes(data)?;
+        let (preamble_rows, data) = split_preamble(data);
+
+        let evaluated = self.evaluate_candidates(data);
+
+        let mut best = None;
+        let mut pruned = Vec::new();
+        let mut rejections = Vec::new();
+        let mut limit_hit = None;
+        let mut expired = None;
+        for (idx, (dialect, outcome)) in evaluated.candidates.into_iter().enumerate() {
+            let reason = match outcome {
+                Scored::Parsed(table, score) if evaluated.winner == Some(idx) => {
+                    best = Some((dialect, table, score));
+                    continue;
+                }
+                Scored::Parsed(table, f64::NEG_INFINITY) => table.rejection(self.scorer.name()),
+                Scored::Parsed(..) => continue,
+                Scored::Rejected(reason) => reason,
+                Scored::Pruned(reason) => {
+                    pruned.push(CandidateRejection { dialect, reason });
+                    continue;
+                }
+                Scored::Expired(budget) => {
+                    expired = Some(budget);
+                    continue;
+                }
+            };
+            if let Rejection::Limit(limit) = reason {
+                limit_hit.get_or_insert(limit);
+            }
+            rejections.push(CandidateRejection { dialect, reason });
+        }
+
+        #[cfg(feature = "tracing")]
+        match &best {
+            Some((dialect, _, score)) => tracing::debug!(%dialect, score, "selected dialect"),
+            None => tracing::debug!(rejected = rejections.len(), "no valid dialect"),
+        }
+
+        let result = match best {
+            Some((dialect, table, score)) => Some(SniffResult {
+                pruned,
+                preamble_rows,
+                ..self.build_result(dialect, &table, score)
+            }),
+            None => {
+                rejections.splice(0..0, pruned);
+                None
+            }
+        };
+        if let Some(budget) = expired {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(?budget, "time budget expired");
+            return Err(SnifferError::LimitExceeded {
+                limit: Limit::TimeBudget(budget),
+                best: result.map(Box::new),
+            });
+        }
+        match (result, limit_hit) {
+            (Some(result), _) => Ok(result),
+            (None, Some(limit)) => Err(SnifferError::LimitExceeded { limit, best: None }),
+            (None, None) => Err(no_valid_dialect(data, rejections)),
+        }
+    }
+
+    /// Parse and score every candidate dialect of `data` and pick the winner
+    ///
+    /// Candidates the prefilter prunes are not parsed, except that
+    /// single-column candidates are scored when every other candidate was
+    /// rejected without hitting a limit. Candidates after the first are
+    /// skipped once the time budget has expired.
+    pub(crate) fn evaluate_candidates(&self, data: &str) -> Candidates {
+        let started = Instant::now();
+        let dialects = self.generate_potential_dialects(data);
+        let prefilter = Prefilter::new(data, &dialects);
+        let mut outcomes: Vec<Option<Scored>> = dialects
+            .iter()
+            .map(|dialect| {
+                prefilter
+                    .prune(dialect, self.scorer.as_ref())
+                    .map(|reason| {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(%dialect, %reason, "pruned candidate");
+                        Scored::Pruned(reason)
+                    })
+            })
+            .collect();
+
+        let cache = SniffCache::new(data);
+        let mut scored = 0;
+        for deferred in [false, true] {
+            let round: Vec<usize> = if deferred {
+                if outcomes.iter().flatten().any(Scored::is_final) {
+                    break;
+                }
+                (0..dialects.len())
+                    .filter(|&idx| {
+                        matches!(
+                            outcomes[idx],
+                            Some(Scored::Pruned(Rejection::DelimiterNotFound))
+                        )
+                    })
+                    .collect()
+            } else {
+                (0..dialects.len())
+                    .filter(|&idx| outcomes[idx].is_none())
+                    .collect()
+            };
+            let round_dialects: Vec<Dialect> =
+                round.iter().map(|&idx| dialects[idx].clone()).collect();
+            let round_outcomes =
+                self.score_candidates(data, &round_dialects, scored, started, &cache);
+            for (&idx, outcome) in round.iter().zip(round_outcomes) {
+                outcomes[idx] = Some(outcome);
+            }
+            scored += round.len();
+        }
+
+        let candidates: Vec<(Dialect, Scored)> = dialects
+            .into_iter()
+            .zip(outcomes)
+            .map(|(dialect, outcome)| {
+                (
+                    dialect,
+                    outcome.expect("every candidate is pruned or scored"),
+                )
+            })
+            .collect();
+        let winner = best_candidate(
+            candidates.iter().map(|(_, outcome)| outcome.score()),
+            |idx| quote_occurs(data, &candidates[idx].0),
+        );
+        Candidates { candidates, winner }
+    }
+
+    /// Score candidate dialects in order, the first being candidate number
+    /// `first_idx` of this sniff
+    ///
+    /// With `rayon`, candidates are scored concurrently; either way outcomes
+    /// are returned in candidate order, so ties go to the earliest.
+    fn score_candidates<'a>(
+        &'a self,
+        data: &'a str,
+        dialects: &'a [Dialect],
+        first_idx: usize,
+        started: Instant,
+        cache: &'a SniffCache,
+    ) -> impl Iterator<Item = Scored> + 'a {
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            // Worker threads don't see a scoped subscriber, so carry it over
+            #[cfg(feature = "tracing")]
+            let (dispatch, parent) = (
+                tracing::dispatcher::get_default(Clone::clone),
+                tracing::Span::current(),
+            );
+            dialects
+                .par_iter()
+                .enumerate()
+                .map(|(idx, dialect)| {
+                    let idx = first_idx + idx;
+                    #[cfg(feature = "tracing")]
+                    return tracing::dispatcher::with_default(&dispatch, || {
+                        let _parent = parent.enter();
+                        self.score_candidate(data, dialect, idx, started, cache)
+                    });
+                    #[cfg(not(feature = "tracing"))]
+                    self.score_candidate(data, dialect, idx, started, cache)
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+        }
+        #[cfg(not(feature = "rayon"))]
+        dialects.iter().enumerate().map(move |(idx, dialect)| {
+            self.score_candidate(data, dialect, first_idx + idx, started, cache)
+        })
+    }
+
+    /// Parse and score one candidate dialect
+    ///
+    /// Candidates after the first are skipped once the time budget has expired.
+    fn score_candidate(
+        &self,
+        data: &str,
+        dialect: &Dialect,
+        idx: usize,
+        started: Instant,
+        cache: &SniffCache,
+    ) -> Scored {
+        if idx > 0
+            && let Some(budget) = self.expired_budget(started)
+        {
+            return Scored::Expired(budget);
+        }
+
+        #[cfg(feature = "tracing")]
+        let _span = dialect_span(dialect).entered();
+        match self.parse_candidate(data, dialect, cache) {
+            Ok(table) => {
+                let score = self.scorer.score(self, data, dialect, &table);
+                #[cfg(feature = "tracing")]
+                if score == f64::NEG_INFINITY {
+                    tracing::debug!(reason = %table.rejection(self.scorer.name()), "rejected candidate");
+                } else {
+                    tracing::debug!(score, "scored candidate");
+                }
+                Scored::Parsed(table, score)
+            }
+            Err(reason) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(%reason, "rejected candidate");
+                Scored::Rejected(reason)
+            }
+        }
+    }
+
+    /// Refuse `data` when it is larger than the byte limit
+    pub(crate) fn check_max_bytes(&self, data: &str) -> Result<(), SnifferError> {
+        match self.limits.max_bytes {
+            Some(max_bytes) if data.len() > max_bytes => Err(SnifferError::LimitExceeded {
+                limit: Limit::Bytes(max_bytes),
+                best: None,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// The time budget, if it has expired since `started`
+    pub(crate) fn expired_budget(&self, started: Instant) -> Option<Duration> {
+        self.limits
+            .time_budget
+            .filter(|&budget| started.elapsed() > budget)
+    }
+
+    /// Parse `data` with every candidate dialect, separating the candidates
+    /// that fail to parse
+    ///
+    /// With `started`, candidates after the first are rejected with
+    /// `Limit::TimeBudget` once the time budget has expired.
+    pub(crate) fn candidate_tables(
+        &self,
+        data: &str,
+        started: Option<Instant>,
+    ) -> (Vec<(Dialect, CandidateTable)>, Vec<CandidateRejection>) {
+        let mut tables = Vec::new();
+        let mut rejections = Vec::new();
+        let cache = SniffCache::new(data);
+        for (idx, dialect) in self
+            .generate_potential_dialects(data)
+            .into_iter()
+            .enumerate()
+        {
+            if idx > 0
+                && let Some(budget) = started.and_then(|started| self.expired_budget(started))
+            {
+                rejections.push(CandidateRejection {
+                    dialect,
+                    reason: Rejection::Limit(Limit::TimeBudget(budget)),
+                });
+                continue;
+            }
+            #[cfg(feature = "tracing")]
+            let _span = dialect_span(&dialect).entered();
+            match self.parse_candidate(data, &dialect, &cache) {
+                Ok(table) => tables.push((dialect, table)),
+                Err(reason) => rejections.push(CandidateRejection { dialect, reason }),
+            }
+        }
+        (tables, rejections)
+    }
+
+    pub(crate) fn build_result(
+        &self,
+        dialect: Dialect,
+        table: &CandidateTable,
+        score: f64,
+    ) -> SniffResult {
+        SniffResult {
+            columns: self.profile_columns(table),
+            num_rows: table.num_rows,
+            score,
+            dialect,
+            pruned: Vec::new(),
+            preamble_rows: 0,
+        }
+    }
+
+    /// Detect the dialect of a reader and deserialize all of its records into `T`
+    ///
+    /// The whole input is buffered; only the first `max_rows` lines are used for detection.
+    pub fn deserialize<T: DeserializeOwned, R: Read>(
+        &self,
+        mut reader: R,
+    ) -> Result<Vec<T>, SnifferError> {
+        let mut data = String::new();
+        reader.read_to_string(&mut data)?;
+        self.deserialize_from_string(&data)
+    }
+
+    /// Detect the dialect of string data and deserialize all of its records into `T`
+    ///
+    /// When a header row is detected, fields are mapped to struct members by name;
+    /// otherwise they are mapped by position.
+    pub fn deserialize_from_string<T: DeserializeOwned>(
+        &self,
+        data: &str,
+    ) -> Result<Vec<T>, SnifferError> {
+        let sample = self.sample(data);
+        let rows_read = sample.lines().count();
+        if rows_read < self.min_rows {
+            return Err(SnifferError::InvalidInput {
+                rows_read,
+                bytes_read: sample.len(),
+                min_rows: self.min_rows,
+            });
+        }
+        let dialect = self.sniff_from_string(sample)?;
+        let (_, data) = split_preamble(data);
+
+        let mut builder = dialect.reader_builder();
+        // Sniffed dialects end records at `\n`; `CRLF` also accepts `\r\n`, so
+        // a trailing `\r` does not end up in the last field of each row
+        if matches!(dialect.terminator, csv::Terminator::Any(b'\n')) {
+            builder.terminator(csv::Terminator::CRLF);
+        }
+        let mut reader = builder.from_reader(data.as_bytes());
+        let mut rows = Vec::new();
+        for result in reader.deserialize() {
+            match result {
+                Ok(row) => rows.push(row),
+                Err(source) => {
+                    let line = source
+                        .position()
+                        .map_or_else(|| reader.position().line(), csv::Position::line);
+                    return Err(SnifferError::Deserialize {
+                        dialect,
+                        line,
+                        source,
+                    });
                 }
             }
         }
 
-        best_dialect.ok_or(SnifferError::NoValidDialect)
+        Ok(rows)
+    }
+
+    /// Return the leading slice of `data` containing at most `max_rows` lines
+    fn sample<'a>(&self, data: &'a str) -> &'a str {
+        match data
+            .match_indices('\n')
+            .nth(self.max_rows.saturating_sub(1))
+        {
+            Some((idx, _)) if self.max_rows > 0 => &data[..=idx],
+            _ => data,
+        }
     }
 
     /// Generate potential CSV dialects based on data analysis
@@ -213,14 +1002,30 @@ impl Sniffer {
         // Common delimiters to test
         let delimiters = [b',', b';', b'\t', b'|', b' '];
 
-        // Common quote characters
-        let quote_chars = [Some(b'"'), Some(b'\''), None];
+        // Common quote characters, escaped with a backslash when one precedes
+        // a quote character in the data and doubled otherwise
+        let escape_for = |quote: Option<u8>| {
+            quote
+                .filter(|&quote| {
+                    data.as_bytes()
+                        .windows(2)
+                        .any(|pair| pair == [b'\\', quote])
+                })
+                .map(|_| b'\\')
+        };
+        let quote_chars = [Some(b'"'), Some(b'\''), None].map(|quote| (quote, escape_for(quote)));
 
         // Analyze first few lines to get hints
         let lines: Vec<&str> = data.lines().take(10).collect();
 
+        // The first line's ending decides between CRLF and LF records
+        let terminator = match data.split_once('\n') {
+            Some((line, _)) if line.ends_with('\r') => csv::Terminator::CRLF,
+            _ => csv::Terminator::Any(b'\n'),
+        };
+
         for &delimiter in &delimiters {
-            for &quote_char in &quote_chars {
+            for &(quote_char, escape) in &quote_chars {
                 // Skip combinations that don't make sense
                 if delimiter == b' ' && quote_char.is_none() {
                     continue; // Space delimiter usually needs quotes
@@ -229,9 +1034,9 @@ impl Sniffer {
                 let dialect = Dialect {
                     delimiter,
                     quote_char,
-                    escape: None,
-                    has_headers: self.detect_headers(&lines, delimiter),
-                    terminator: csv::Terminator::Any(b'\n'),
+                    escape,
+                    has_headers: self.detect_headers(&lines, delimiter, quote_char, escape),
+                    terminator,
                     quoting: if quote_char.is_some() {
                         csv::QuoteStyle::Necessary
                     } else {
@@ -243,21 +1048,43 @@ impl Sniffer {
             }
         }
 
+        #[cfg(feature = "tracing")]
+        tracing::debug!(candidates = dialects.len(), "generated candidate dialects");
         dialects
     }
 
     /// Detect if the CSV likely has headers
-    fn detect_headers(&self, lines: &[&str], delimiter: u8) -> bool {
+    fn detect_headers(
+        &self,
+        lines: &[&str],
+        delimiter: u8,
+        quote_char: Option<u8>,
+        escape: Option<u8>,
+    ) -> bool {
         if lines.len() < 2 {
             return false;
         }
 
-        let first_line = lines[0];
-        let second_line = lines[1];
-
-        // Count fields in first two lines
-        let first_fields: Vec<&str> = first_line.split(delimiter as char).collect();
-        let second_fields: Vec<&str> = second_line.split(delimiter as char).collect();
+        // Split the first two lines as the candidate dialect would, so quoted
+        // fields are unquoted and may contain the delimiter
+        let mut builder = ReaderBuilder::new();
+        builder
+            .has_headers(false)
+            .flexible(true)
+            .delimiter(delimiter);
+        match quote_char {
+            Some(quote) => builder.quote(quote),
+            None => builder.quoting(false),
+        };
+        if escape.is_some() {
+            builder.escape(escape).double_quote(false);
+        }
+        let sample = format!("{}\n{}\n", lines[0], lines[1]);
+        let mut records = builder.from_reader(sample.as_bytes()).into_records();
+        let (Some(Ok(first_fields)), Some(Ok(second_fields))) = (records.next(), records.next())
+        else {
+            return false;
+        };
 
         if first_fields.len() != second_fields.len() {
             return false;
@@ -280,68 +1107,104 @@ impl Sniffer {
 
     /// Check if a field looks numeric
     fn is_numeric(&self, field: &str) -> bool {
-        if field.is_empty() {
-            return false;
-        }
+        // Every integer is also a float
+        classify_field(field).contains(&DataType::Float)
+    }
 
-        let regexes = get_type_regexes();
-        regexes.get(&DataType::Integer).unwrap().is_match(field)
-            || regexes.get(&DataType::Float).unwrap().is_match(field)
+    /// Parse a candidate dialect, reusing the table of an earlier candidate
+    /// that tokenizes `data` identically
+    pub(crate) fn parse_candidate(
+        &self,
+        data: &str,
+        dialect: &Dialect,
+        cache: &SniffCache,
+    ) -> Result<CandidateTable, Rejection> {
+        cache.table(dialect, || self.parse_with_dialect(data, dialect, cache))
     }
 
     /// Parse CSV data with a specific dialect
-    fn parse_with_dialect(&self, data: &str, dialect: &Dialect) -> Result<Table, SnifferError> {
-        let mut builder = ReaderBuilder::new();
-        builder.delimiter(dialect.delimiter);
-
-        if let Some(quote) = dialect.quote_char {
-            builder.quote(quote);
+    ///
+    /// Fields are typed as they are tokenized; only per-column counters are
+    /// kept, so memory use does not grow with the number of rows.
+    fn parse_with_dialect(
+        &self,
+        data: &str,
+        dialect: &Dialect,
+        cache: &SniffCache,
+    ) -> Result<CandidateTable, Rejection> {
+        let mut reader = RecordReader::new(data, dialect, self.limits);
+
+        let mut row_length_counts = BTreeMap::new();
+        let headers = if dialect.has_headers {
+            // Like csv::Reader, a sample without records has an empty header
+            let headers: Vec<String> = match reader.read()? {
+                Some(record) => record.fields().map(str::to_string).collect(),
+                None => Vec::new(),
+            };
+            *row_length_counts.entry(headers.len()).or_insert(0) += 1;
+            Some(headers)
         } else {
-            builder.quoting(false);
-        }
-
-        builder.has_headers(dialect.has_headers);
-        builder.terminator(dialect.terminator);
-
-        let mut reader = builder.from_reader(Cursor::new(data));
-        let mut records = Vec::new();
-        let mut num_columns = 0;
-
-        // Read all records
-        for result in reader.records() {
-            let record = result?;
+            None
+        };
+        let mut num_columns = headers.as_ref().map_or(0, Vec::len);
+        let mut column_type_counts = Vec::new();
+        let mut value_shapes = Vec::new();
+        let mut num_rows = 0;
+        let mut dropped_rows = 0;
+        let mut first_dropped = None;
+        let mut field_types = cache.field_types();
+
+        while let Some(record) = reader.read()? {
+            *row_length_counts.entry(record.len()).or_insert(0) += 1;
             if num_columns == 0 {
                 num_columns = record.len();
             } else if record.len() != num_columns {
                 // Inconsistent column count - this dialect might not be correct
+                dropped_rows += 1;
+                first_dropped.get_or_insert((record.line, record.len()));
                 continue;
             }
-            records.push(record);
-        }
-
-        if records.is_empty() {
-            return Err(SnifferError::InvalidInput);
-        }
 
-        // Analyze data types for each column
-        let mut column_types = vec![Vec::new(); num_columns];
-
-        for record in &records {
-            for (col_idx, field) in record.iter().enumerate() {
-                if col_idx < num_columns {
-                    let data_type = self.detect_data_type(field);
-                    column_types[col_idx].push(data_type);
+            // Analyze the data type of each field
+            if column_type_counts.is_empty() {
+                column_type_counts = vec![HashMap::new(); num_columns];
+                value_shapes = vec![HashMap::new(); num_columns];
+            }
+            for (col_idx, field) in record.fields().enumerate() {
+                let data_type =
+                    field_types.get(col_idx, field, |field| self.detect_data_type(field));
+                if let Some(shape) = ValueShape::of(field.trim(), &data_type) {
+                    value_shapes[col_idx]
+                        .entry(data_type.clone())
+                        .and_modify(|seen: &mut ValueShape| seen.merge(shape))
+                        .or_insert(shape);
                 }
+                *column_type_counts[col_idx].entry(data_type).or_insert(0) += 1;
             }
+            num_rows += 1;
         }
 
-        let num_rows = records.len();
+        if num_rows == 0 {
+            return Err(Rejection::NoRecords);
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            rows = num_rows,
+            columns = num_columns,
+            dropped_rows,
+            "parsed candidate"
+        );
 
-        Ok(Table {
-            records,
-            column_types,
+        Ok(CandidateTable {
+            headers,
+            column_type_counts,
+            value_shapes,
+            row_length_counts,
             num_columns,
             num_rows,
+            dropped_rows,
+            first_dropped,
         })
     }
 
@@ -353,53 +1216,38 @@ impl Sniffer {
             return DataType::Empty;
         }
 
-        let regexes = get_type_regexes();
-
-        // Check each data type in order of specificity
-        let type_order = [
-            DataType::Boolean,
-            DataType::Integer,
-            DataType::Float,
-            DataType::DateTime,
-            DataType::Date,
-            DataType::Time,
-            DataType::Email,
-            DataType::Url,
-            DataType::Phone,
-            DataType::Currency,
-            DataType::Percentage,
-        ];
-
-        for data_type in &type_order {
-            #[allow(clippy::collapsible_if)]
-            if let Some(regex) = regexes.get(data_type) {
-                if regex.is_match(trimmed) {
-                    return data_type.clone();
-                }
-            }
-        }
-
-        DataType::Text
+        // Check each detector in order of priority, classifying the field
+        // against all built-in types at once when the first one is reached
+        let mut builtin_types = None;
+        self.detectors
+            .iter()
+            .find(|detector| match detector.builtin_type() {
+                Some(data_type) => builtin_types
+                    .get_or_insert_with(|| classify_field(trimmed))
+                    .contains(data_type),
+                None => detector.matches(trimmed),
+            })
+            .map_or(DataType::Text, |detector| detector.data_type())
     }
 
     /// Calculate table uniformity score using the Table Uniformity Method
-    fn calculate_table_uniformity(&self, table: &Table) -> f64 {
-        if table.num_rows == 0 || table.num_columns == 0 {
+    pub(crate) fn calculate_table_uniformity(&self, table: &CandidateTable) -> f64 {
+        // Tables with inconsistent row widths are not uniform
+        if table.num_rows == 0 || table.num_columns == 0 || table.dropped_rows > 0 {
             return f64::NEG_INFINITY;
         }
 
         let mut total_score = 0.0;
         let mut valid_columns = 0;
 
-        for col_idx in 0..table.num_columns {
-            let column_types = &table.column_types[col_idx];
-            if column_types.is_empty() {
+        for type_counts in &table.column_type_counts {
+            let total_count = type_counts.values().sum();
+            if total_count == 0 {
                 continue;
             }
 
             // Calculate type consistency for this column
-            let type_counts = self.count_types(column_types);
-            let column_score = self.calculate_column_uniformity(&type_counts, column_types.len());
+            let column_score = self.calculate_column_uniformity(type_counts, total_count);
 
             total_score += column_score;
             valid_columns += 1;
@@ -412,8 +1260,8 @@ impl Sniffer {
         // Average uniformity across all columns
         let avg_uniformity = total_score / f64::from(valid_columns);
 
-        // Bonus for consistent row length
-        let row_consistency_bonus = 1.0; // All rows have same length if we got here
+        // Bonus for consistent row length (all rows have same length if we got here)
+        let row_consistency_bonus = self.scoring_profile.row_consistency_bonus;
 
         // Penalty for too many empty fields
         let empty_penalty = self.calculate_empty_penalty(table);
@@ -421,13 +1269,43 @@ impl Sniffer {
         avg_uniformity.mul_add(row_consistency_bonus, -empty_penalty)
     }
 
-    /// Count occurrences of each data type in a column
-    fn count_types(&self, types: &[DataType]) -> HashMap<DataType, usize> {
-        let mut counts = HashMap::new();
-        for data_type in types {
-            *counts.entry(data_type.clone()).or_insert(0) += 1;
-        }
-        counts
+    /// Find the most common non-empty type, breaking ties by `DataType` order
+    fn dominant_type(type_counts: &HashMap<DataType, usize>) -> Option<(DataType, usize)> {
+        type_counts
+            .iter()
+            .filter(|&(data_type, &count)| *data_type != DataType::Empty && count > 0)
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(data_type, &count)| (data_type.clone(), count))
+    }
+
+    /// Build the per-column profiles of a parsed table
+    fn profile_columns(&self, table: &CandidateTable) -> Vec<ColumnProfile> {
+        table
+            .column_type_counts
+            .iter()
+            .enumerate()
+            .map(|(col_idx, type_counts)| {
+                let name = table.column_name(col_idx);
+
+                let data_type = Self::dominant_type(type_counts)
+                    .map_or(DataType::Empty, |(data_type, _)| data_type);
+
+                let shape = table.value_shapes[col_idx]
+                    .get(&data_type)
+                    .copied()
+                    .unwrap_or_default();
+
+                ColumnProfile {
+                    name,
+                    data_type,
+                    empty_count: type_counts.get(&DataType::Empty).copied().unwrap_or(0),
+                    max_integer_digits: shape.integer_digits,
+                    max_fraction_digits: shape.fraction_digits,
+                    fixed_scale: shape.fixed_scale,
+                    standard_form: shape.standard_form,
+                }
+            })
+            .collect()
     }
 
     /// Calculate uniformity score for a single column
@@ -440,68 +1318,61 @@ impl Sniffer {
             return 0.0;
         }
 
-        // Find the most common type (excluding empty)
-        let mut max_count = 0;
-        let mut dominant_type = DataType::Text;
-
-        for (data_type, &count) in type_counts {
-            if *data_type != DataType::Empty && count > max_count {
-                max_count = count;
-                dominant_type = data_type.clone();
-            }
-        }
+        let (dominant_type, max_count) =
+            Self::dominant_type(type_counts).unwrap_or((DataType::Text, 0));
 
         // Calculate uniformity as ratio of dominant type
         let uniformity = max_count as f64 / total_count as f64;
 
-        // Apply type-specific weights
-        let type_weight = match dominant_type {
-            DataType::Integer | DataType::Float => 1.2,
-            DataType::Date | DataType::DateTime | DataType::Time => 1.1,
-            DataType::Email | DataType::Url => 1.1,
-            DataType::Boolean => 1.0,
-            DataType::Text => 0.8,
-            DataType::Empty => 0.1,
-            _ => 1.0,
-        };
+        uniformity * self.type_weight(&dominant_type)
+    }
 
-        uniformity * type_weight
+    /// Weight applied to a column whose dominant type is `data_type`
+    fn type_weight(&self, data_type: &DataType) -> f64 {
+        let profile = &self.scoring_profile;
+        profile.type_weight(data_type.name()).unwrap_or_else(|| {
+            self.detectors
+                .iter()
+                .find(|detector| detector.data_type() == *data_type)
+                .map_or(profile.default_weight, |detector| detector.weight())
+        })
     }
 
     /// Calculate penalty for empty fields
-    fn calculate_empty_penalty(&self, table: &Table) -> f64 {
+    fn calculate_empty_penalty(&self, table: &CandidateTable) -> f64 {
         let total_fields = table.num_rows * table.num_columns;
         if total_fields == 0 {
             return 0.0;
         }
 
-        let mut empty_count = 0;
-        for column_types in &table.column_types {
-            empty_count += column_types
-                .iter()
-                .filter(|&t| *t == DataType::Empty)
-                .count();
-        }
-
-        let empty_ratio = empty_count as f64 / total_fields as f64;
-        empty_ratio * 0.5 // Penalty factor
+        let empty_ratio = table.empty_count() as f64 / total_fields as f64;
+        empty_ratio * self.scoring_profile.empty_penalty
     }
 }
 
 /// Represents a CSV dialect configuration
-#[derive(Debug, Clone)]
+///
+/// Serializes with readable encodings (e.g. `"delimiter": "\t"`, `"terminator": "CRLF"`,
+/// `"quoting": "necessary"`); missing fields take their `Default` values when deserializing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Dialect {
     /// Field delimiter (e.g., comma, semicolon, tab)
+    #[serde(with = "serde_fmt::byte")]
     pub delimiter: u8,
     /// Quote character (e.g., double quote, single quote)
+    #[serde(with = "serde_fmt::opt_byte")]
     pub quote_char: Option<u8>,
     /// Escape character
+    #[serde(with = "serde_fmt::opt_byte")]
     pub escape: Option<u8>,
     /// Whether to treat the first row as headers
     pub has_headers: bool,
     /// Line terminator
+    #[serde(with = "serde_fmt::terminator")]
     pub terminator: csv::Terminator,
     /// Whether quotes are required around all fields
+    #[serde(with = "serde_fmt::quote_style")]
     pub quoting: csv::QuoteStyle,
 }
 
@@ -515,6 +1386,51 @@ impl PartialEq for Dialect {
     }
 }
 
+impl Dialect {
+    /// Create a `csv::ReaderBuilder` configured to read data in this dialect
+    #[must_use]
+    pub fn reader_builder(&self) -> ReaderBuilder {
+        let mut builder = ReaderBuilder::new();
+        builder.delimiter(self.delimiter);
+
+        if let Some(quote) = self.quote_char {
+            builder.quote(quote);
+        } else {
+            builder.quoting(false);
+        }
+
+        if let Some(escape) = self.escape {
+            builder.escape(Some(escape));
+            builder.double_quote(false);
+        }
+
+        builder.has_headers(self.has_headers);
+        builder.terminator(self.terminator);
+        builder
+    }
+}
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn byte(b: Option<u8>) -> String {
+            match b {
+                Some(b'\t') => "'\\t'".to_string(),
+                Some(b) => format!("'{}'", b.escape_ascii()),
+                None => "none".to_string(),
+            }
+        }
+
+        write!(
+            f,
+            "delimiter {}, quote {}, escape {}, headers {}",
+            byte(Some(self.delimiter)),
+            byte(self.quote_char),
+            byte(self.escape),
+            if self.has_headers { "yes" } else { "no" }
+        )
+    }
+}
+
 impl Default for Dialect {
     fn default() -> Self {
         Self {
@@ -589,11 +1505,11 @@ mod tests {
 
         // With headers
         let lines_with_headers = vec!["name,age,city", "John,25,NYC", "Jane,30,LA"];
-        assert!(sniffer.detect_headers(&lines_with_headers, b','));
+        assert!(sniffer.detect_headers(&lines_with_headers, b',', Some(b'"'), None));
 
         // Without headers
         let lines_without_headers = vec!["John,25,NYC", "Jane,30,LA", "Bob,35,SF"];
-        assert!(!sniffer.detect_headers(&lines_without_headers, b','));
+        assert!(!sniffer.detect_headers(&lines_without_headers, b',', Some(b'"'), None));
     }
 
     #[test]
@@ -621,4 +1537,282 @@ mod tests {
         assert_eq!(dialect.delimiter, b',');
         assert_eq!(dialect.quote_char, Some(b'"'));
     }
+
+    #[derive(Debug, serde::Deserialize, PartialEq)]
+    struct Person {
+        name: String,
+        age: u32,
+        city: String,
+    }
+
+    #[test]
+    fn test_deserialize_with_headers() {
+        let csv_data = "city;name;age\nNYC;John;25\nLA;Jane;30\nSF;Bob;35";
+        let sniffer = Sniffer::new();
+        let people: Vec<Person> = sniffer.deserialize_from_string(csv_data).unwrap();
+
+        assert_eq!(people.len(), 3);
+        assert_eq!(
+            people[1],
+            Person {
+                name: "Jane".to_string(),
+                age: 30,
+                city: "LA".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_deserialize_crlf() {
+        #[derive(Debug, serde::Deserialize, PartialEq)]
+        struct Row {
+            name: String,
+            age: u32,
+        }
+
+        let rows: Vec<Row> = Sniffer::new()
+            .deserialize_from_string("name,age\r\nJo,25\r\nAl,31\r\n")
+            .unwrap();
+        assert_eq!(
+            rows,
+            [
+                Row {
+                    name: "Jo".to_string(),
+                    age: 25
+                },
+                Row {
+                    name: "Al".to_string(),
+                    age: 31
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_preamble_is_skipped() {
+        let data = "# Exported 2024-05-01, ops\n#\nid;name\n10;Ann\n11;Bo\n";
+        let result = Sniffer::new().detect_from_string(data).unwrap();
+        assert_eq!(result.preamble_rows, 2);
+        assert_eq!(result.dialect.delimiter, b';');
+        assert!(result.dialect.has_headers);
+        assert_eq!(result.num_rows, 2);
+
+        // A `#` column heading is part of the table
+        let result = Sniffer::new()
+            .detect_from_string("#,name\n1,Ann\n2,Bo\n")
+            .unwrap();
+        assert_eq!(result.preamble_rows, 0);
+        assert_eq!(result.columns[0].name, "#");
+
+        // So is a header row starting with `#` that has the table's shape
+        for (data, delimiter) in [
+            ("#id,name\n10,Ann\n20,Bo\n30,Cy\n", b','),
+            ("#CHROM\tPOS\tREF\n1\t100\tA\n1\t200\tG\n2\t300\tT\n", b'\t'),
+            (
+                "## fileformat=x\n#CHROM\tPOS\tREF\n1\t100\tA\n1\t200\tG\n",
+                b'\t',
+            ),
+        ] {
+            let result = Sniffer::new().detect_from_string(data).unwrap();
+            assert_eq!(result.dialect.delimiter, delimiter, "{data:?}");
+            assert_eq!(result.preamble_rows, usize::from(data.starts_with("##")));
+            assert!(result.dialect.has_headers, "{data:?}");
+            assert!(result.columns[0].name.starts_with('#'));
+        }
+    }
+
+    #[test]
+    fn test_crlf_terminator_is_detected() {
+        let data = "id,name\r\n10,Ann\r\n11,Bo\r\n12,Cy\r\n";
+        let sniffer = Sniffer::new();
+        for result in [
+            sniffer.detect_from_string(data).unwrap(),
+            sniffer.detect(data.as_bytes()).unwrap(),
+        ] {
+            assert!(matches!(result.dialect.terminator, csv::Terminator::CRLF));
+            assert_eq!(result.columns[1].name, "name");
+        }
+
+        let dialect = sniffer
+            .sniff_from_string("id,name\n10,Ann\n11,Bo\n")
+            .unwrap();
+        assert!(matches!(dialect.terminator, csv::Terminator::Any(b'\n')));
+    }
+
+    #[test]
+    fn test_escaped_and_single_quotes() {
+        let dialect = Sniffer::new()
+            .sniff_from_string("id,note\n10,\"say \\\"hi\\\", ok\"\n11,plain\n")
+            .unwrap();
+        assert_eq!(dialect.quote_char, Some(b'"'));
+        assert_eq!(dialect.escape, Some(b'\\'));
+
+        // Text quoted with `'` parses the same under `"`; the quote that
+        // occurs wins the tie
+        let dialect = Sniffer::new()
+            .sniff_from_string("'Ann',10\n'Bo',11\n'Cy',12\n")
+            .unwrap();
+        assert_eq!(dialect.quote_char, Some(b'\''));
+        assert_eq!(dialect.escape, None);
+    }
+
+    #[test]
+    fn test_detect_result() {
+        let csv_data = "id,name,joined\n10,John,2023-01-15\n11,,2022-06-20\n12,Bob,2021-03-10";
+        let sniffer = Sniffer::new();
+        let result = sniffer.detect_from_string(csv_data).unwrap();
+
+        assert_eq!(result.dialect.delimiter, b',');
+        assert_eq!(result.num_rows, 3);
+        let names: Vec<&str> = result.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "name", "joined"]);
+        assert_eq!(result.columns[0].data_type, DataType::Integer);
+        assert_eq!(result.columns[1].data_type, DataType::Text);
+        assert_eq!(result.columns[1].empty_count, 1);
+        assert_eq!(result.columns[2].data_type, DataType::Date);
+    }
+
+    #[test]
+    fn test_ties_go_to_earliest_candidate() {
+        // Every candidate reads one text column, so all scores tie; the
+        // first candidate wins, with or without the `rayon` feature
+        let sniffer = Sniffer::new();
+        for _ in 0..10 {
+            let dialect = sniffer.sniff_from_string("alpha\nbeta\ngamma\n").unwrap();
+            assert_eq!(dialect.delimiter, b',');
+            assert_eq!(dialect.quote_char, Some(b'"'));
+        }
+    }
+
+    #[test]
+    fn test_dialect_serde_round_trip() {
+        let dialect = Dialect {
+            delimiter: b'\t',
+            quote_char: None,
+            escape: Some(b'\\'),
+            has_headers: false,
+            terminator: csv::Terminator::CRLF,
+            quoting: csv::QuoteStyle::Never,
+        };
+        let json = serde_json::to_value(&dialect).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "delimiter": "\t",
+                "quote_char": null,
+                "escape": "\\",
+                "has_headers": false,
+                "terminator": "CRLF",
+                "quoting": "never"
+            })
+        );
+
+        let restored: Dialect = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, dialect);
+        assert!(matches!(restored.terminator, csv::Terminator::CRLF));
+
+        let partial: Dialect = serde_json::from_str(r#"{"delimiter": ";"}"#).unwrap();
+        assert_eq!(partial.delimiter, b';');
+        assert_eq!(partial.quote_char, Some(b'"'));
+    }
+
+    #[test]
+    fn test_dialect_serde_round_trip_non_ascii_bytes() {
+        let dialect = Dialect {
+            delimiter: 0xA7,
+            quote_char: Some(0xFF),
+            escape: Some(b'\\'),
+            terminator: csv::Terminator::Any(0x85),
+            ..Dialect::default()
+        };
+        let json = serde_json::to_value(&dialect).unwrap();
+        assert_eq!(json["delimiter"], "\\xA7");
+        assert_eq!(json["quote_char"], "\\xFF");
+        assert_eq!(json["escape"], "\\");
+        assert_eq!(json["terminator"], "\\x85");
+        let restored: Dialect = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, dialect);
+
+        // ASCII bytes have a one-character form, and `é` is not a byte
+        for invalid in [r#""\\x2C""#, r#""é""#, r#""\\xZZ""#] {
+            let json = format!(r#"{{"delimiter": {invalid}}}"#);
+            assert!(serde_json::from_str::<Dialect>(&json).is_err(), "{json}");
+        }
+    }
+
+    #[test]
+    fn test_deserialize_positional_and_error_line() {
+        let sniffer = Sniffer::new();
+        let people: Vec<Person> = sniffer
+            .deserialize(Cursor::new("John|25|NYC\nJane|30|LA\nBob|35|SF"))
+            .unwrap();
+        assert_eq!(people[2].name, "Bob");
+
+        let csv_data = "John|25|NYC\nJane|30|LA\nBob|35|SF\nAlice|old|Chicago";
+        let err = sniffer
+            .deserialize_from_string::<Person>(csv_data)
+            .unwrap_err();
+        match err {
+            SnifferError::Deserialize { dialect, line, .. } => {
+                assert_eq!(dialect.delimiter, b'|');
+                assert_eq!(line, 4);
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tracing_spans_and_events() {
+        use std::sync::Mutex;
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata};
+
+        /// Records span names and the field names of events
+        #[derive(Default)]
+        struct Recorder {
+            next_id: AtomicU64,
+            spans: Mutex<Vec<String>>,
+            event_fields: Mutex<Vec<String>>,
+        }
+
+        impl tracing::Subscriber for &'static Recorder {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                self.spans
+                    .lock()
+                    .unwrap()
+                    .push(span.metadata().name().to_string());
+                Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, event: &Event<'_>) {
+                let fields = event.metadata().fields().iter().map(|f| f.to_string());
+                self.event_fields.lock().unwrap().extend(fields);
+            }
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let recorder: &'static Recorder = Box::leak(Box::default());
+        tracing::subscriber::with_default(recorder, || {
+            Sniffer::new()
+                .sniff("a;b\n1;2\n3,4;5\n".as_bytes())
+                .unwrap();
+        });
+
+        let spans = recorder.spans.lock().unwrap();
+        assert_eq!(spans[..2], ["detect", "detect_from_string"]);
+        // Only ',' and ';' occur and ',' splits the lines raggedly, so the
+        // prefilter prunes all but the three ';' candidates before any span
+        assert_eq!(spans.iter().filter(|name| *name == "candidate").count(), 3);
+        let fields = recorder.event_fields.lock().unwrap();
+        for field in ["rows", "candidates", "score", "reason", "dialect"] {
+            assert!(fields.iter().any(|f| f == field), "no event with {field}");
+        }
+    }
 }
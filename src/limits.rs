@@ -0,0 +1,150 @@
+//! Resource limits for sniffing untrusted input.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// Bounds on the work a `Sniffer` does for one input
+///
+/// All limits are off by default. Field length and column limits apply per
+/// candidate dialect: a candidate whose parse exceeds them is rejected (e.g. a
+/// quote character that leaves a quote unterminated), and detection only fails
+/// with `SnifferError::LimitExceeded` when every candidate was rejected that
+/// way. The byte limit applies to the sampled input as a whole. The time budget
+/// is checked between candidates, so the first candidate is always scored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// Longest field (in bytes) a candidate may produce
+    pub max_field_len: Option<usize>,
+    /// Most fields a candidate may produce in one record
+    pub max_columns: Option<usize>,
+    /// Largest input (in bytes) accepted for detection
+    pub max_bytes: Option<usize>,
+    /// Wall-clock budget for scoring candidates
+    pub time_budget: Option<Duration>,
+}
+
+impl Limits {
+    /// Limits suited to untrusted uploads: 1 MiB fields, 10,000 columns,
+    /// 16 MiB of input and a one second budget
+    #[must_use]
+    pub const fn untrusted() -> Self {
+        Self {
+            max_field_len: Some(1 << 20),
+            max_columns: Some(10_000),
+            max_bytes: Some(16 << 20),
+            time_budget: Some(Duration::from_secs(1)),
+        }
+    }
+}
+
+/// The limit reported by `SnifferError::LimitExceeded`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "max", rename_all = "snake_case")]
+pub enum Limit {
+    FieldLength(usize),
+    Columns(usize),
+    Bytes(usize),
+    TimeBudget(Duration),
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FieldLength(max) => write!(f, "field longer than {max} bytes"),
+            Self::Columns(max) => write!(f, "record with more than {max} columns"),
+            Self::Bytes(max) => write!(f, "input larger than {max} bytes"),
+            Self::TimeBudget(budget) => write!(f, "time budget of {budget:?} expired"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Rejection, Sniffer, SnifferError};
+
+    #[test]
+    fn test_per_candidate_limits() {
+        // The unterminated quote swallows the rest of the input as one field
+        let csv_data = format!("id,name,note\n1,a,\"open\n{}", "2,b,yyyyyyyyyy\n".repeat(5));
+        let mut sniffer = Sniffer::new();
+        sniffer.limits.max_field_len = Some(32);
+        let dialect = sniffer.sniff_from_string(&csv_data).unwrap();
+        assert_eq!(dialect.delimiter, b',');
+        assert_ne!(dialect.quote_char, Some(b'"'));
+
+        let wide = format!("{}\n{}\n", "a,".repeat(50), "1,".repeat(50));
+        sniffer.limits.max_columns = Some(20);
+        assert!(matches!(
+            sniffer.detect_from_string(&wide),
+            Err(SnifferError::LimitExceeded {
+                limit: Limit::Columns(20),
+                best: None
+            })
+        ));
+
+        sniffer.limits.max_bytes = Some(16);
+        let err = sniffer.sniff("a,b\n1,2\n3,4\n5,6\n7,8\n".as_bytes());
+        assert!(matches!(
+            err,
+            Err(SnifferError::LimitExceeded {
+                limit: Limit::Bytes(16),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_time_budget_returns_best_so_far() {
+        let mut sniffer = Sniffer::new();
+        sniffer.limits.time_budget = Some(Duration::ZERO);
+
+        match sniffer.detect_from_string("a,b,c\n1,2,3\n4,5,6") {
+            Err(SnifferError::LimitExceeded {
+                limit: Limit::TimeBudget(_),
+                best: Some(best),
+            }) => assert_eq!(best.dialect.delimiter, b','),
+            other => panic!("expected an expired budget, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_ensemble_and_explain_apply_limits() {
+        let data = "a,b,c\n1,2,3\n4,5,6";
+        let mut sniffer = Sniffer::new();
+        sniffer.limits.time_budget = Some(Duration::ZERO);
+
+        match sniffer.detect_ensemble(data, &crate::Ensemble::default()) {
+            Err(SnifferError::LimitExceeded {
+                limit: Limit::TimeBudget(_),
+                best: Some(best),
+            }) => assert_eq!(best.dialect.delimiter, b','),
+            other => panic!("expected an expired budget, got {other:?}"),
+        }
+        let explanation = sniffer.explain(data).unwrap();
+        let expired = explanation.candidates.iter().filter(|candidate| {
+            matches!(
+                candidate.rejection,
+                Some(Rejection::Limit(Limit::TimeBudget(_)))
+            )
+        });
+        assert!(expired.count() > 0);
+
+        sniffer.limits.max_bytes = Some(8);
+        for err in [
+            sniffer.explain(data).err(),
+            sniffer
+                .detect_ensemble(data, &crate::Ensemble::default())
+                .err(),
+        ] {
+            assert!(matches!(
+                err,
+                Some(SnifferError::LimitExceeded {
+                    limit: Limit::Bytes(8),
+                    best: None
+                })
+            ));
+        }
+    }
+}
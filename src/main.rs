@@ -1,10 +1,15 @@
 #![cfg(feature = "cli")]
 
 use clap::{Parser, ValueEnum};
-use csv_qsniffer::{Dialect, Sniffer};
+use csv_qsniffer::{
+    Aggregation, ConsistencyScorer, Dialect, Ensemble, EnsembleResult, Explanation,
+    FrequencyScorer, Limit, Limits, ScoringProfile, SniffResult, Sniffer, SnifferError, SqlDialect,
+    TableUniformityScorer, sql_table_name,
+};
 use std::fs::File;
 use std::io::{self, BufReader, Read};
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(name = "csv-qsniffer")]
@@ -27,9 +32,102 @@ struct Cli {
     #[arg(long, default_value_t = 2)]
     min_rows: usize,
 
+    /// Dialect scoring algorithm
+    #[arg(long, value_enum, default_value_t = ScorerKind::Tum)]
+    scorer: ScorerKind,
+
+    /// Combine the tum, clevercsv and python scorers instead of using --scorer
+    #[arg(long)]
+    ensemble: bool,
+
+    /// How ensemble scores are combined
+    #[arg(long, value_enum, default_value_t = AggregationKind::Vote)]
+    aggregation: AggregationKind,
+
+    /// Reject candidate dialects producing fields longer than this (bytes)
+    #[arg(long, value_name = "BYTES")]
+    max_field_len: Option<usize>,
+
+    /// Reject candidate dialects producing records with more columns than this
+    #[arg(long, value_name = "N")]
+    max_columns: Option<usize>,
+
+    /// Refuse inputs larger than this (bytes)
+    #[arg(long, value_name = "BYTES")]
+    max_bytes: Option<usize>,
+
+    /// Stop scoring candidates after this many milliseconds and use the best so far
+    #[arg(long, value_name = "MS")]
+    time_budget: Option<u64>,
+
+    /// Scoring profile: a preset (default, sparse, text-heavy) or a .json/.toml file
+    #[arg(long, value_name = "PRESET|FILE")]
+    profile: Option<String>,
+
     /// Show detailed analysis information
     #[arg(short, long)]
     verbose: bool,
+
+    /// Print the score breakdown of every candidate dialect instead of the
+    /// detected dialect (as JSON with --format json)
+    #[arg(long)]
+    explain: bool,
+
+    /// Include the inferred column types (frictionless and csvw formats)
+    #[arg(long)]
+    schema: bool,
+
+    /// SQL dialect for `CREATE TABLE` output (sql format)
+    #[arg(long, value_enum, default_value_t = SqlTarget::Postgres)]
+    sql_dialect: SqlTarget,
+
+    /// Table name for generated SQL (defaults to the input file name)
+    #[arg(long)]
+    table: Option<String>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum ScorerKind {
+    /// Table Uniformity Method
+    Tum,
+    /// `CleverCSV` pattern x type consistency
+    Clevercsv,
+    /// Python `csv.Sniffer`-compatible frequency heuristic
+    Python,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum AggregationKind {
+    /// Weighted mean of normalized scores
+    Vote,
+    /// Rank aggregation (Borda count)
+    Rank,
+}
+
+impl From<AggregationKind> for Aggregation {
+    fn from(kind: AggregationKind) -> Self {
+        match kind {
+            AggregationKind::Vote => Self::WeightedVote,
+            AggregationKind::Rank => Self::Rank,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum SqlTarget {
+    Postgres,
+    Sqlite,
+    Duckdb,
+}
+
+impl From<SqlTarget> for SqlDialect {
+    fn from(target: SqlTarget) -> Self {
+        match target {
+            SqlTarget::Postgres => Self::Postgres,
+            SqlTarget::Sqlite => Self::Sqlite,
+            SqlTarget::Duckdb => Self::DuckDb,
+        }
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -40,6 +138,22 @@ enum OutputFormat {
     Json,
     /// CSV output (`delimiter,quote_char,has_headers,escape`)
     Csv,
+    /// Frictionless Data dialect descriptor
+    Frictionless,
+    /// W3C CSVW metadata
+    Csvw,
+    /// `DuckDB` `read_csv(...)` call
+    Duckdb,
+    /// Polars `pl.read_csv(...)` call
+    Polars,
+    /// pandas `pd.read_csv(...)` call
+    Pandas,
+    /// `PostgreSQL` `COPY ... FROM ...` statement
+    Postgres,
+    /// Python `csv.Dialect` subclass
+    Python,
+    /// `CREATE TABLE` statement for the inferred columns
+    Sql,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -49,61 +163,167 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut sniffer = Sniffer::new();
     sniffer.max_rows = cli.max_rows;
     sniffer.min_rows = cli.min_rows;
+    sniffer.limits = Limits {
+        max_field_len: cli.max_field_len,
+        max_columns: cli.max_columns,
+        max_bytes: cli.max_bytes,
+        time_budget: cli.time_budget.map(Duration::from_millis),
+    };
+    match cli.scorer {
+        ScorerKind::Tum => sniffer.set_scorer(TableUniformityScorer),
+        ScorerKind::Clevercsv => sniffer.set_scorer(ConsistencyScorer),
+        ScorerKind::Python => sniffer.set_scorer(FrequencyScorer),
+    };
+    if let Some(profile) = &cli.profile {
+        sniffer.scoring_profile = match ScoringProfile::preset(profile) {
+            Some(preset) => preset,
+            None => ScoringProfile::load(profile)?,
+        };
+    }
 
-    // Read input data
-    let input_data = match &cli.input {
-        Some(path) if path.to_str() == Some("-") => {
-            if cli.verbose {
-                eprintln!("Reading from stdin...");
-            }
-            let mut buffer = String::new();
-            io::stdin().read_to_string(&mut buffer)?;
-            buffer
-        }
-        Some(path) => {
+    // Read input data, stopping one byte past `--max-bytes` so an oversized
+    // input is refused without being read whole
+    let source: Box<dyn Read> = match &cli.input {
+        Some(path) if path.to_str() != Some("-") => {
             if cli.verbose {
                 eprintln!("Reading from file: {}", path.display());
             }
-            let file = File::open(path)?;
-            let mut reader = BufReader::new(file);
-            let mut buffer = String::new();
-            reader.read_to_string(&mut buffer)?;
-            buffer
+            Box::new(BufReader::new(File::open(path)?))
         }
-        None => {
+        _ => {
             if cli.verbose {
                 eprintln!("Reading from stdin...");
             }
-            let mut buffer = String::new();
-            io::stdin().read_to_string(&mut buffer)?;
-            buffer
+            Box::new(io::stdin())
         }
     };
+    let cap = cli.max_bytes.map_or(u64::MAX, |max| max as u64 + 1);
+    let mut bytes = Vec::new();
+    source.take(cap).read_to_end(&mut bytes)?;
+    if let Some(max) = cli.max_bytes
+        && bytes.len() > max
+    {
+        let e = SnifferError::LimitExceeded {
+            limit: Limit::Bytes(max),
+            best: None,
+        };
+        eprintln!("Error detecting CSV dialect: {e}");
+        std::process::exit(1);
+    }
+    let input_data = String::from_utf8(bytes)?;
 
     if input_data.trim().is_empty() {
         eprintln!("Error: No input data provided");
         std::process::exit(1);
     }
 
+    if cli.explain {
+        let explanation = match sniffer.explain(&input_data) {
+            Ok(explanation) => explanation,
+            Err(e) => {
+                eprintln!("Error explaining CSV dialect: {e}");
+                std::process::exit(1);
+            }
+        };
+        if cli.format == OutputFormat::Json {
+            println!("{}", serde_json::to_string_pretty(&explanation)?);
+        } else {
+            print_explanation(&explanation);
+        }
+        return Ok(());
+    }
+
     // Detect dialect
-    let dialect = match sniffer.sniff_from_string(&input_data) {
-        Ok(dialect) => dialect,
+    let detected = if cli.ensemble {
+        let ensemble = Ensemble::default().with_aggregation(cli.aggregation.into());
+        sniffer
+            .detect_ensemble(&input_data, &ensemble)
+            .map(|ensemble| (ensemble.result.clone(), Some(ensemble)))
+    } else {
+        sniffer
+            .detect_from_string(&input_data)
+            .map(|result| (result, None))
+    };
+    let (result, ensemble) = match detected {
+        Ok(detected) => detected,
+        Err(SnifferError::LimitExceeded {
+            limit,
+            best: Some(best),
+        }) => {
+            eprintln!("Warning: {limit}; using the best dialect found so far");
+            (*best, None)
+        }
         Err(e) => {
             eprintln!("Error detecting CSV dialect: {e}");
+            if let SnifferError::NoValidDialect { rejections, .. } = &e {
+                for rejection in rejections {
+                    eprintln!("  {rejection}");
+                }
+            }
             std::process::exit(1);
         }
     };
 
     // Output results
+    let table = sql_table_name(
+        &cli.table
+            .clone()
+            .unwrap_or_else(|| table_name(cli.input.as_ref())),
+    );
+    let path = match &cli.input {
+        Some(path) if path.to_str() != Some("-") => path.display().to_string(),
+        _ => "data.csv".to_string(),
+    };
     match cli.format {
-        OutputFormat::Human => print_human_readable(&dialect, cli.verbose),
-        OutputFormat::Json => print_json(&dialect)?,
-        OutputFormat::Csv => print_csv(&dialect),
+        OutputFormat::Human => {
+            print_human_readable(&result.dialect, cli.verbose);
+            if cli.verbose && !result.pruned.is_empty() {
+                println!("Pruned candidates:");
+                for pruned in &result.pruned {
+                    println!("  {pruned}");
+                }
+            }
+            if let Some(ensemble) = &ensemble {
+                print_ensemble(ensemble);
+            }
+        }
+        OutputFormat::Json => match &ensemble {
+            Some(ensemble) => println!("{}", serde_json::to_string_pretty(ensemble)?),
+            None => print_json(&result)?,
+        },
+        OutputFormat::Csv => print_csv(&result.dialect),
+        OutputFormat::Frictionless => println!(
+            "{}",
+            serde_json::to_string_pretty(&result.to_frictionless(cli.schema))?
+        ),
+        OutputFormat::Csvw => println!(
+            "{}",
+            serde_json::to_string_pretty(&result.to_csvw(cli.schema))?
+        ),
+        OutputFormat::Duckdb => println!("SELECT * FROM {};", result.dialect.to_duckdb(&path)),
+        OutputFormat::Polars => println!("{}", result.dialect.to_polars(&path)),
+        OutputFormat::Pandas => println!("{}", result.dialect.to_pandas(&path)),
+        OutputFormat::Postgres => {
+            println!("{};", result.dialect.to_postgres_copy(&table, &path));
+        }
+        OutputFormat::Python => print!("{}", result.dialect.to_python_dialect("SniffedDialect")),
+        OutputFormat::Sql => println!("{}", result.create_table(&table, cli.sql_dialect.into())),
     }
 
     Ok(())
 }
 
+/// Derive a table name for generated SQL from the input file name
+fn table_name(input: Option<&PathBuf>) -> String {
+    input
+        .filter(|path| path.to_str() != Some("-"))
+        .and_then(|path| path.file_stem())
+        .map_or_else(
+            || "data".to_string(),
+            |stem| stem.to_string_lossy().into_owned(),
+        )
+}
+
 fn print_human_readable(dialect: &Dialect, verbose: bool) {
     println!("CSV Dialect Detection Results:");
     println!("==============================");
@@ -133,31 +353,64 @@ fn print_human_readable(dialect: &Dialect, verbose: bool) {
     }
 }
 
-fn print_json(dialect: &Dialect) -> Result<(), Box<dyn std::error::Error>> {
-    let json_output = serde_json::json!({
-        "delimiter": dialect.delimiter as char,
-        "delimiter_byte": dialect.delimiter,
-        "quote_char": dialect.quote_char.map(|c| c as char),
-        "quote_char_byte": dialect.quote_char,
-        "escape": dialect.escape.map(|c| c as char),
-        "escape_byte": dialect.escape,
-        "has_headers": dialect.has_headers,
-        "terminator": match dialect.terminator {
-            csv::Terminator::CRLF => "CRLF",
-            csv::Terminator::Any(b'\n') => "LF",
-            csv::Terminator::Any(b'\r') => "CR",
-            _ => "Other",
-        },
-        "quoting": match dialect.quoting {
-            csv::QuoteStyle::Always => "Always",
-            csv::QuoteStyle::Necessary => "Necessary",
-            csv::QuoteStyle::NonNumeric => "NonNumeric",
-            csv::QuoteStyle::Never => "Never",
-            _ => "Other",
+fn print_ensemble(ensemble: &EnsembleResult) {
+    println!(
+        "Scorer agreement: {:.0}%{}",
+        ensemble.agreement * 100.0,
+        if ensemble.ambiguous {
+            " (ambiguous)"
+        } else {
+            ""
         }
-    });
+    );
+    for vote in &ensemble.votes {
+        match &vote.dialect {
+            Some(dialect) => println!("  {}: {dialect}", vote.scorer),
+            None => println!("  {}: no valid dialect", vote.scorer),
+        }
+    }
+}
+
+fn print_explanation(explanation: &Explanation) {
+    println!(
+        "Candidates scored by {} (* marks the detected dialect):",
+        explanation.scorer
+    );
+    for candidate in &explanation.candidates {
+        println!();
+        let marker = if candidate.winner { "*" } else { " " };
+        match (&candidate.score, &candidate.rejection) {
+            (Some(score), _) => println!("{marker} {}: score {score:.4}", candidate.dialect),
+            (None, Some(rejection)) => {
+                println!("{marker} {}: rejected, {rejection}", candidate.dialect);
+            }
+            (None, None) => println!("{marker} {}: rejected", candidate.dialect),
+        }
+        if candidate.columns.is_empty() {
+            continue;
+        }
+        println!(
+            "    rows {}, dropped {}, empty penalty {:.4}",
+            candidate.num_rows, candidate.dropped_rows, candidate.empty_penalty
+        );
+        println!(
+            "    {:<20} {:<12} {:>10} {:>8}",
+            "Column", "Type", "Uniformity", "Weight"
+        );
+        for column in &candidate.columns {
+            println!(
+                "    {:<20} {:<12} {:>10.3} {:>8.3}",
+                column.name,
+                column.data_type.as_ref().map_or("(empty)", |t| t.name()),
+                column.uniformity,
+                column.weight
+            );
+        }
+    }
+}
 
-    println!("{}", serde_json::to_string_pretty(&json_output)?);
+fn print_json(result: &SniffResult) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", serde_json::to_string_pretty(result)?);
     Ok(())
 }
 
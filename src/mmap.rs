@@ -0,0 +1,221 @@
+//! Sniffing memory-mapped files (requires the `mmap` feature).
+//!
+//! The file is mapped rather than read, and only a sample of it is scored:
+//! its first lines, evenly spaced blocks of lines and its last lines. When
+//! the first `max_rows` lines already span the file, or fields may span
+//! lines, the head alone is sniffed straight from the mapping.
+
+use crate::{SniffResult, Sniffer, SnifferError};
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fs::File;
+use std::path::Path;
+
+/// Blocks sampled between the head and the tail of a file
+const STRIDED_BLOCKS: usize = 8;
+
+/// Result of sniffing a file with `Sniffer::sniff_path`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSniffResult {
+    /// Detection result for the sample
+    pub result: SniffResult,
+    /// Size of the file in bytes
+    pub file_size: u64,
+    /// Data rows in the file (excluding the header row), extrapolated from
+    /// the average record length in the sample
+    pub estimated_rows: u64,
+    /// Whether the sample was the whole file, making `estimated_rows` exact
+    pub whole_file: bool,
+}
+
+impl Sniffer {
+    /// Detect the dialect of a file by memory-mapping it and sampling its
+    /// head, evenly spaced blocks and tail, `max_rows` lines in total
+    ///
+    /// Sampled lines are borrowed from the mapping; they are only copied
+    /// into one buffer when blocks from several parts of the file are
+    /// joined. Files whose first lines have an unbalanced quote character
+    /// may have fields spanning lines, so only their head is sampled.
+    pub fn sniff_path(&self, path: impl AsRef<Path>) -> Result<FileSniffResult, SnifferError> {
+        let file = File::open(path)?;
+        let file_size = file.metadata()?.len();
+        if file_size == 0 {
+            return Err(SnifferError::InvalidInput {
+                rows_read: 0,
+                bytes_read: 0,
+                min_rows: self.min_rows,
+            });
+        }
+        // SAFETY: the mapping is only read while this call holds it. A file
+        // truncated concurrently by another process can fault the read, the
+        // same caveat every memory-mapped reader carries.
+        let map = unsafe { Mmap::map(&file)? };
+
+        let (sample, whole_file) = self.sample_mapping(&map)?;
+        let rows_read = sample.lines().count();
+        if rows_read < self.min_rows {
+            return Err(SnifferError::InvalidInput {
+                rows_read,
+                bytes_read: sample.len(),
+                min_rows: self.min_rows,
+            });
+        }
+        let result = self.detect_from_string(&sample)?;
+
+        let header = u64::from(result.dialect.has_headers);
+        let estimated_rows = if whole_file {
+            result.num_rows as u64
+        } else {
+            // Records per byte of the sample, header included, over the file
+            let records = (result.num_rows as u64 + header) as f64;
+            let estimate = (file_size as f64 * records / sample.len() as f64).round() as u64;
+            estimate.saturating_sub(header)
+        };
+
+        Ok(FileSniffResult {
+            result,
+            file_size,
+            estimated_rows,
+            whole_file,
+        })
+    }
+
+    /// The lines of `data` to sniff, and whether they are all of it
+    fn sample_mapping<'a>(&self, data: &'a [u8]) -> Result<(Cow<'a, str>, bool), SnifferError> {
+        let text = |bytes| {
+            std::str::from_utf8(bytes)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+        };
+        let max_rows = self.max_rows.max(1);
+        let head = &data[..lines_end(data, 0, max_rows)];
+        if head.len() == data.len() {
+            return Ok((Cow::Borrowed(text(data)?), true));
+        }
+        let head_only = Ok((Cow::Borrowed(text(head)?), false));
+        if spans_lines(head) {
+            return head_only;
+        }
+
+        let head_end = lines_end(data, 0, max_rows.div_ceil(2));
+        let block_lines = ((max_rows - max_rows.div_ceil(2)) / (STRIDED_BLOCKS + 1)).max(1);
+        let tail_start = lines_start(data, block_lines);
+        if tail_start < head.len() {
+            return head_only;
+        }
+
+        let mut sample = String::with_capacity(head.len());
+        sample.push_str(text(&data[..head_end])?);
+        let mut prev_end = head_end;
+        for block in 1..=STRIDED_BLOCKS {
+            let offset = head_end + (tail_start - head_end) * block / (STRIDED_BLOCKS + 1);
+            // Start at the line following the offset
+            let start = match data[offset..].iter().position(|&b| b == b'\n') {
+                Some(idx) => (offset + idx + 1).max(prev_end),
+                None => break,
+            };
+            let end = lines_end(data, start, block_lines).min(tail_start);
+            if start < end {
+                sample.push_str(text(&data[start..end])?);
+                prev_end = end;
+            }
+        }
+        sample.push_str(text(&data[tail_start.max(prev_end)..])?);
+        Ok((Cow::Owned(sample), false))
+    }
+}
+
+/// Offset just past the `lines`-th line starting at `from`, or the end
+fn lines_end(data: &[u8], from: usize, lines: usize) -> usize {
+    data[from..]
+        .iter()
+        .enumerate()
+        .filter(|&(_, &b)| b == b'\n')
+        .nth(lines - 1)
+        .map_or(data.len(), |(idx, _)| from + idx + 1)
+}
+
+/// Offset of the first of the last `lines` lines
+fn lines_start(data: &[u8], lines: usize) -> usize {
+    let body = data.strip_suffix(b"\n").unwrap_or(data);
+    body.iter()
+        .enumerate()
+        .rev()
+        .filter(|&(_, &b)| b == b'\n')
+        .nth(lines - 1)
+        .map_or(0, |(idx, _)| idx + 1)
+}
+
+/// Whether a line has an odd number of a candidate quote character, so a
+/// quoted field may continue on the next line
+fn spans_lines(data: &[u8]) -> bool {
+    data.split(|&b| b == b'\n').any(|line| {
+        [b'"', b'\'']
+            .iter()
+            .any(|&quote| line.iter().filter(|&&b| b == quote).count() % 2 == 1)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_file(data: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(data.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_small_file_is_sniffed_whole() {
+        let file = write_file("id;name\n1;Ann\n2;Bo\n3;Cy\n");
+        let sniffed = Sniffer::new().sniff_path(file.path()).unwrap();
+        assert_eq!(sniffed.result.dialect.delimiter, b';');
+        assert!(sniffed.whole_file);
+        assert_eq!(sniffed.estimated_rows, 3);
+        assert_eq!(sniffed.file_size, 24);
+    }
+
+    #[test]
+    fn test_large_file_samples_head_blocks_and_tail() {
+        let mut data = String::from("id,name,score\n");
+        for row in 0..10_000 {
+            data.push_str(&format!("{row},name{},{}\n", row % 7, row % 100));
+        }
+        let mut sniffer = Sniffer::new();
+        sniffer.max_rows = 100;
+
+        let (sample, whole_file) = sniffer.sample_mapping(data.as_bytes()).unwrap();
+        assert!(!whole_file);
+        assert!(sample.starts_with("id,name,score\n0,"));
+        assert!(sample.ends_with("9999,name3,99\n"));
+        let ids = sample
+            .lines()
+            .skip(1)
+            .map(|line| line.split(',').next().unwrap());
+        let middle = ids.filter(|id| (1_000..9_000).contains(&id.parse::<u32>().unwrap()));
+        assert!(middle.count() >= STRIDED_BLOCKS);
+        assert!(sample.lines().count() <= 100);
+
+        let file = write_file(&data);
+        let sniffed = sniffer.sniff_path(file.path()).unwrap();
+        assert_eq!(sniffed.result.dialect.delimiter, b',');
+        assert_eq!(sniffed.file_size, data.len() as u64);
+        assert!(sniffed.estimated_rows.abs_diff(10_000) < 1_000);
+    }
+
+    #[test]
+    fn test_fields_spanning_lines_sample_head_only() {
+        let mut data = String::from("id,comment\n");
+        for row in 0..1_000 {
+            data.push_str(&format!("{row},\"first\nsecond\"\n"));
+        }
+        let mut sniffer = Sniffer::new();
+        sniffer.max_rows = 20;
+        let (sample, whole_file) = sniffer.sample_mapping(data.as_bytes()).unwrap();
+        assert!(matches!(sample, Cow::Borrowed(_)));
+        assert!(!whole_file);
+        assert_eq!(sample.lines().count(), 20);
+    }
+}
@@ -0,0 +1,259 @@
+//! Cheap candidate pruning before the full parse.
+//!
+//! One pass over the sample counts every candidate delimiter per line. A
+//! delimiter that never occurs can only split the sample into a single
+//! column. When a candidate's quote character never occurs either, its
+//! records are exactly the sample's non-empty lines, so lines with differing
+//! delimiter counts are rows the parse would drop.
+//!
+//! Candidates whose delimiter is missing are only deferred: the sniffer
+//! scores them if every other candidate is rejected, so ragged or
+//! single-column data still gets the dialect it got before pruning.
+
+use crate::{Dialect, DialectScorer, Rejection};
+
+/// Per-line occurrence statistics of one candidate delimiter
+#[derive(Debug, Clone, Copy, Default)]
+struct DelimiterStats {
+    delimiter: u8,
+    /// Occurrences in the whole sample
+    total: usize,
+    /// Fields in the first non-empty line
+    expected: usize,
+    /// Non-empty lines whose field count differs from the first
+    ragged_lines: usize,
+    /// Line (1-based, as the csv parser reports it) and field count of the
+    /// first ragged line
+    first_ragged: Option<(u64, usize)>,
+}
+
+/// Delimiter and quote statistics of a sample, computed once per sniff
+pub(crate) struct Prefilter {
+    delimiters: Vec<DelimiterStats>,
+    /// Which bytes occur in the sample
+    present: [bool; 256],
+    /// Every `\r` ends a non-empty line, so CRLF records are lines too
+    crlf_lines: bool,
+}
+
+impl Prefilter {
+    pub(crate) fn new(data: &str, dialects: &[Dialect]) -> Self {
+        let mut delimiters: Vec<DelimiterStats> = Vec::new();
+        // Index + 1 of each candidate delimiter's stats, by byte
+        let mut slots = [0; 256];
+        for dialect in dialects {
+            let slot = &mut slots[usize::from(dialect.delimiter)];
+            if *slot == 0 {
+                delimiters.push(DelimiterStats {
+                    delimiter: dialect.delimiter,
+                    ..DelimiterStats::default()
+                });
+                *slot = delimiters.len();
+            }
+        }
+
+        let mut present = [false; 256];
+        let mut counts = vec![0; delimiters.len()];
+        let mut line = 1;
+        // Like csv::Reader, a record is reported at the line following the
+        // previous record, even if blank lines were skipped before it
+        let mut record_line = 1;
+        let mut first_line = true;
+        let mut line_len = 0;
+        // A final terminator closes a last line that lacks one
+        for &b in data.as_bytes().iter().chain(Some(&b'\n')) {
+            if b != b'\n' {
+                present[usize::from(b)] = true;
+                line_len += 1;
+                if let Some(idx) = slots[usize::from(b)].checked_sub(1) {
+                    counts[idx] += 1;
+                }
+                continue;
+            }
+
+            // The csv parser skips empty lines
+            if line_len > 0 {
+                for (stats, count) in delimiters.iter_mut().zip(&mut counts) {
+                    let fields = *count + 1;
+                    stats.total += *count;
+                    if first_line {
+                        stats.expected = fields;
+                    } else if fields != stats.expected {
+                        stats.ragged_lines += 1;
+                        stats.first_ragged.get_or_insert((record_line, fields));
+                    }
+                    *count = 0;
+                }
+                first_line = false;
+                record_line = line + 1;
+            }
+            line += 1;
+            line_len = 0;
+        }
+
+        // CRLF also ends records at a lone `\r` and skips blank `\r\n` lines
+        let crlf_lines = !present[usize::from(b'\r')]
+            || data.split('\n').all(|line| {
+                line.find('\r')
+                    .is_none_or(|idx| idx > 0 && idx + 1 == line.len())
+            });
+
+        Self {
+            delimiters,
+            present,
+            crlf_lines,
+        }
+    }
+
+    /// Why `dialect` cannot be selected under `scorer`, if it provably cannot
+    ///
+    /// A delimiter missing from the sample is only pruned when another
+    /// candidate delimiter occurs, so single-column data is still detected.
+    pub(crate) fn prune(&self, dialect: &Dialect, scorer: &dyn DialectScorer) -> Option<Rejection> {
+        let stats = self
+            .delimiters
+            .iter()
+            .find(|stats| stats.delimiter == dialect.delimiter)?;
+
+        if stats.total == 0 && self.delimiters.iter().any(|stats| stats.total > 0) {
+            return Some(Rejection::DelimiterNotFound);
+        }
+
+        let lines_are_records = dialect
+            .quote_char
+            .is_none_or(|quote| !self.present[usize::from(quote)])
+            && match dialect.terminator {
+                csv::Terminator::Any(b'\n') => true,
+                csv::Terminator::CRLF => self.crlf_lines,
+                _ => false,
+            };
+        match stats.first_ragged {
+            Some((line, fields)) if lines_are_records && scorer.rejects_ragged_rows() => {
+                Some(Rejection::InconsistentRows {
+                    dropped_rows: stats.ragged_lines,
+                    line,
+                    fields,
+                    expected: stats.expected,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConsistencyScorer, Sniffer, TableUniformityScorer};
+
+    fn dialect(delimiter: u8, quote_char: Option<u8>) -> Dialect {
+        Dialect {
+            delimiter,
+            quote_char,
+            terminator: csv::Terminator::Any(b'\n'),
+            ..Dialect::default()
+        }
+    }
+
+    #[test]
+    fn test_prunes_absent_delimiters_and_ragged_lines() {
+        let data = "a,b;c\n\n1,2\n\n3,4;5\n";
+        let dialects = [
+            dialect(b',', Some(b'"')),
+            dialect(b';', Some(b'"')),
+            dialect(b'|', None),
+        ];
+        let prefilter = Prefilter::new(data, &dialects);
+        let prune = |dialect| prefilter.prune(dialect, &TableUniformityScorer);
+
+        assert_eq!(prune(&dialects[0]), None);
+        // Matches what parsing the candidate would report
+        let parsed = Sniffer::new()
+            .parse_with_dialect(data, &dialects[1], &crate::cache::SniffCache::new(data))
+            .unwrap();
+        assert_eq!(prune(&dialects[1]), Some(parsed.rejection("tum")));
+        assert_eq!(
+            prune(&dialects[1]),
+            Some(Rejection::InconsistentRows {
+                dropped_rows: 1,
+                line: 2,
+                fields: 1,
+                expected: 2,
+            })
+        );
+        assert_eq!(prune(&dialects[2]), Some(Rejection::DelimiterNotFound));
+        // Other scorers may still rank ragged tables
+        assert_eq!(prefilter.prune(&dialects[1], &ConsistencyScorer), None);
+    }
+
+    #[test]
+    fn test_crlf_lines_are_records_unless_cr_stands_alone() {
+        let crlf = Dialect {
+            terminator: csv::Terminator::CRLF,
+            ..dialect(b';', None)
+        };
+        let prune = |data| {
+            Prefilter::new(data, std::slice::from_ref(&crlf)).prune(&crlf, &TableUniformityScorer)
+        };
+        assert!(matches!(
+            prune("a;b\r\n1;2;3\r\n"),
+            Some(Rejection::InconsistentRows { line: 2, .. })
+        ));
+        // The parser skips blank CRLF lines and splits records at a lone `\r`
+        assert_eq!(prune("a;b\r\n\r\n1;2\r\n"), None);
+        assert_eq!(prune("a;b\r\n1\r2\r\n"), None);
+    }
+
+    #[test]
+    fn test_keeps_quoted_and_single_column_candidates() {
+        let data = "a;\"b\nc\"\n1;2\n";
+        let quoted = dialect(b';', Some(b'"'));
+        let prefilter = Prefilter::new(data, std::slice::from_ref(&quoted));
+        assert_eq!(prefilter.prune(&quoted, &TableUniformityScorer), None);
+
+        // No candidate delimiter occurs: every candidate is a single column
+        let data = "name\nJo\nAl\n";
+        let dialects = [dialect(b',', None), dialect(b';', None)];
+        let prefilter = Prefilter::new(data, &dialects);
+        assert!(
+            dialects
+                .iter()
+                .all(|dialect| prefilter.prune(dialect, &TableUniformityScorer).is_none())
+        );
+    }
+
+    #[test]
+    fn test_sniff_result_records_pruned_candidates() {
+        let result = Sniffer::new()
+            .detect_from_string("a;b\n1;2\n3;4\n")
+            .unwrap();
+        assert_eq!(result.dialect.delimiter, b';');
+        // Every other delimiter is absent: 3 quote options each, 2 for space
+        assert_eq!(result.pruned.len(), 11);
+        assert!(
+            result
+                .pruned
+                .iter()
+                .all(|pruned| pruned.reason == Rejection::DelimiterNotFound)
+        );
+    }
+
+    #[test]
+    fn test_single_column_candidates_are_deferred_not_dropped() {
+        // ',' is ragged, so only a delimiter that never occurs can parse it
+        let data = "1,2,3\n4,5\n7,8,9\n";
+        let sniffer = Sniffer::new();
+        let result = sniffer.detect_from_string(data).unwrap();
+        assert_eq!(result.dialect.delimiter, b';');
+        assert_eq!(result.pruned.len(), 3);
+        assert!(
+            result
+                .pruned
+                .iter()
+                .all(|pruned| pruned.dialect.delimiter == b',')
+        );
+
+        let explanation = sniffer.explain(data).unwrap();
+        assert_eq!(explanation.winner().unwrap().dialect, result.dialect);
+    }
+}
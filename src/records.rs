@@ -0,0 +1,242 @@
+//! Streaming record reader for candidate parses.
+//!
+//! Tokenizes a sample with `csv_core` into one reusable field buffer, so a
+//! candidate parse allocates in proportion to its widest record rather than
+//! to the sample. Records and their line numbers match what `csv::Reader`
+//! reports for the same dialect. Field length and column limits are checked
+//! whenever the buffers would grow, so an oversized record is rejected before
+//! it is buffered whole.
+
+use crate::{Dialect, Limit, Limits, Rejection};
+use csv_core::ReadRecordResult;
+
+/// Reads the records of a sample one at a time
+pub(crate) struct RecordReader<'a> {
+    core: csv_core::Reader,
+    input: &'a [u8],
+    fields: Vec<u8>,
+    ends: Vec<usize>,
+    limits: Limits,
+    /// Line the next record is reported at, as `csv::Position::line`
+    line: u64,
+}
+
+/// The current record of a `RecordReader`
+pub(crate) struct Record<'r> {
+    /// Line (1-based) reported for the record
+    pub(crate) line: u64,
+    fields: &'r str,
+    ends: &'r [usize],
+}
+
+impl<'a> RecordReader<'a> {
+    pub(crate) fn new(data: &'a str, dialect: &Dialect, limits: Limits) -> Self {
+        let mut builder = csv_core::ReaderBuilder::new();
+        builder.delimiter(dialect.delimiter);
+        match dialect.quote_char {
+            Some(quote) => builder.quote(quote),
+            None => builder.quoting(false),
+        };
+        if let Some(escape) = dialect.escape {
+            builder.escape(Some(escape)).double_quote(false);
+        }
+        builder.terminator(match dialect.terminator {
+            csv::Terminator::Any(b) => csv_core::Terminator::Any(b),
+            _ => csv_core::Terminator::CRLF,
+        });
+
+        Self {
+            core: builder.build(),
+            input: data.as_bytes(),
+            fields: vec![0; 1024],
+            ends: vec![0; 32],
+            limits,
+            line: 1,
+        }
+    }
+
+    /// Read the next record, or `None` at the end of the sample
+    ///
+    /// Fails on a record with more fields or a longer field than `limits`
+    /// allow, and on a record that is not valid UTF-8, which is only possible
+    /// when a non-ASCII delimiter or quote byte splits a character.
+    pub(crate) fn read(&mut self) -> Result<Option<Record<'_>>, Rejection> {
+        let line = self.line;
+        let (mut len, mut num_fields) = (0, 0);
+        loop {
+            let (result, read, written, ended) = self.core.read_record(
+                self.input,
+                &mut self.fields[len..],
+                &mut self.ends[num_fields..],
+            );
+            self.input = &self.input[read..];
+            len += written;
+            num_fields += ended;
+            match result {
+                ReadRecordResult::InputEmpty => {}
+                ReadRecordResult::OutputFull => {
+                    // The field being read starts where the last one ended
+                    let start = num_fields.checked_sub(1).map_or(0, |idx| self.ends[idx]);
+                    self.check_field_len(len - start)?;
+                    self.fields.resize(self.fields.len() * 2, 0);
+                }
+                ReadRecordResult::OutputEndsFull => {
+                    self.check_columns(num_fields)?;
+                    self.ends.resize(self.ends.len() * 2, 0);
+                }
+                ReadRecordResult::Record => break,
+                ReadRecordResult::End => return Ok(None),
+            }
+        }
+        self.check_columns(num_fields)?;
+        let mut start = 0;
+        for &end in &self.ends[..num_fields] {
+            self.check_field_len(end - start)?;
+            start = end;
+        }
+        // Like csv::Reader, report a record at the line following the
+        // previous record, even if blank lines were skipped before it
+        self.line = self.core.line();
+
+        let invalid = |message| Rejection::Parse {
+            line: Some(line),
+            message: format!("invalid utf-8: {message}"),
+        };
+        let fields =
+            std::str::from_utf8(&self.fields[..len]).map_err(|err| invalid(err.to_string()))?;
+        let ends = &self.ends[..num_fields];
+        if let Some(idx) = ends.iter().position(|&end| !fields.is_char_boundary(end)) {
+            return Err(invalid(format!("field {idx} splits a character")));
+        }
+        Ok(Some(Record { line, fields, ends }))
+    }
+
+    fn check_field_len(&self, len: usize) -> Result<(), Rejection> {
+        match self.limits.max_field_len {
+            Some(max) if len > max => Err(Rejection::Limit(Limit::FieldLength(max))),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_columns(&self, num_fields: usize) -> Result<(), Rejection> {
+        match self.limits.max_columns {
+            Some(max) if num_fields > max => Err(Rejection::Limit(Limit::Columns(max))),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<'r> Record<'r> {
+    /// Number of fields
+    pub(crate) const fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    pub(crate) fn fields(&self) -> impl Iterator<Item = &'r str> + '_ {
+        let fields = self.fields;
+        self.ends.iter().scan(0, move |start, &end| {
+            let field = &fields[*start..end];
+            *start = end;
+            Some(field)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csv::ReaderBuilder;
+
+    /// Records and lines as `csv::Reader` reports them
+    fn csv_records(data: &str, dialect: &Dialect) -> Vec<(u64, Vec<String>)> {
+        let mut builder: ReaderBuilder = dialect.reader_builder();
+        builder.has_headers(false).flexible(true);
+        builder
+            .from_reader(data.as_bytes())
+            .into_records()
+            .map(|record| {
+                let record = record.unwrap();
+                let line = record.position().unwrap().line();
+                (line, record.iter().map(str::to_string).collect())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_matches_csv_reader() {
+        let long = "x".repeat(3000);
+        let samples = [
+            "a,b\n1,2\n",
+            "a,b\n\n\n1,\"2\n3\"\n4,5",
+            "\"a\"\"b\",c\r\n1,2\r\n",
+            "'x;y';z\n1;2\n",
+            &format!("{long},{long}\n1,2\n"),
+            &format!("{}\n", ",".repeat(100)),
+        ];
+        let dialects = [
+            Dialect::default(),
+            Dialect {
+                delimiter: b';',
+                quote_char: Some(b'\''),
+                terminator: csv::Terminator::Any(b'\n'),
+                ..Dialect::default()
+            },
+            Dialect {
+                quote_char: None,
+                escape: Some(b'\\'),
+                ..Dialect::default()
+            },
+        ];
+
+        for data in samples {
+            for dialect in &dialects {
+                let mut reader = RecordReader::new(data, dialect, Limits::default());
+                let mut records = Vec::new();
+                while let Some(record) = reader.read().unwrap() {
+                    let fields = record.fields().map(str::to_string).collect();
+                    records.push((record.line, fields));
+                }
+                assert_eq!(records, csv_records(data, dialect), "{data:?} {dialect}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_limits_stop_buffering() {
+        let limits = Limits {
+            max_field_len: Some(4096),
+            max_columns: Some(100),
+            ..Limits::default()
+        };
+        let data = format!("a,b\n{},1\n", "x".repeat(1 << 20));
+        let mut reader = RecordReader::new(&data, &Dialect::default(), limits);
+        assert!(reader.read().unwrap().is_some());
+        assert_eq!(
+            reader.read().err(),
+            Some(Rejection::Limit(Limit::FieldLength(4096)))
+        );
+        assert!(reader.fields.len() <= 2 * 4096);
+
+        let data = format!("{}\n", ",".repeat(1 << 16));
+        let mut reader = RecordReader::new(&data, &Dialect::default(), limits);
+        assert_eq!(
+            reader.read().err(),
+            Some(Rejection::Limit(Limit::Columns(100)))
+        );
+        assert!(reader.ends.len() <= 2 * 100);
+
+        // A short field at the end of a record is checked too
+        let mut reader = RecordReader::new(
+            "abcd,e\n",
+            &Dialect::default(),
+            Limits {
+                max_field_len: Some(3),
+                ..Limits::default()
+            },
+        );
+        assert_eq!(
+            reader.read().err(),
+            Some(Rejection::Limit(Limit::FieldLength(3)))
+        );
+    }
+}
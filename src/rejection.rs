@@ -0,0 +1,140 @@
+//! Why candidate dialects were rejected, as reported by `SnifferError::NoValidDialect`.
+
+use crate::{Dialect, Limit};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A candidate dialect that could not be used, and why
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CandidateRejection {
+    pub dialect: Dialect,
+    pub reason: Rejection,
+}
+
+impl fmt::Display for CandidateRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.dialect, self.reason)
+    }
+}
+
+/// The reason a candidate dialect was rejected
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rejection {
+    /// The csv parser failed; `line` is 1-based
+    Parse { line: Option<u64>, message: String },
+    /// The data had no records besides the header row
+    NoRecords,
+    /// The delimiter never occurs in the sample, though another candidate
+    /// delimiter does, so the candidate was pruned without parsing while
+    /// another one could be selected
+    DelimiterNotFound,
+    /// Rows differed in width from the header (or first record), and the
+    /// scorer refused a ragged table (or would have, when the candidate was
+    /// pruned without parsing)
+    InconsistentRows {
+        dropped_rows: usize,
+        /// Line of the first dropped row (1-based)
+        line: u64,
+        fields: usize,
+        expected: usize,
+    },
+    /// The named scorer gave the candidate no finite score
+    Scorer(String),
+    /// A record exceeded one of the sniffer's `Limits`
+    Limit(Limit),
+}
+
+impl From<csv::Error> for Rejection {
+    fn from(error: csv::Error) -> Self {
+        Self::Parse {
+            line: error.position().map(csv::Position::line),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse {
+                line: Some(line),
+                message,
+            } => write!(f, "parse error at line {line}: {message}"),
+            Self::Parse {
+                line: None,
+                message,
+            } => write!(f, "parse error: {message}"),
+            Self::NoRecords => write!(f, "no data records"),
+            Self::DelimiterNotFound => write!(f, "delimiter does not occur in the sample"),
+            Self::InconsistentRows {
+                dropped_rows,
+                line,
+                fields,
+                expected,
+            } => write!(
+                f,
+                "{dropped_rows} row(s) with inconsistent width, first at line {line} \
+                 ({fields} fields, expected {expected})"
+            ),
+            Self::Scorer(scorer) => write!(f, "rejected by the {scorer} scorer"),
+            Self::Limit(limit) => write!(f, "{limit}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Sniffer, SnifferError};
+
+    #[test]
+    fn test_no_valid_dialect_reports_rejections() {
+        // Every candidate splits the header into two fields and the row into one
+        let data = "a,b;c|d\te f\n1\n";
+        match Sniffer::new().detect_from_string(data) {
+            Err(SnifferError::NoValidDialect {
+                rows_read,
+                bytes_read,
+                rejections,
+            }) => {
+                assert_eq!(rows_read, 2);
+                assert_eq!(bytes_read, data.len());
+                assert_eq!(rejections.len(), 14);
+                let comma = rejections
+                    .iter()
+                    .find(|r| r.dialect.delimiter == b',' && r.dialect.quote_char == Some(b'"'))
+                    .unwrap();
+                assert_eq!(
+                    comma.reason,
+                    Rejection::InconsistentRows {
+                        dropped_rows: 1,
+                        line: 2,
+                        fields: 1,
+                        expected: 2,
+                    }
+                );
+            }
+            other => panic!("expected NoValidDialect, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_too_few_rows_reports_what_was_read() {
+        let mut sniffer = Sniffer::new();
+        sniffer.min_rows = 3;
+        let err = sniffer.sniff("a,b\n1,2\n".as_bytes()).unwrap_err();
+        assert!(matches!(
+            err,
+            SnifferError::InvalidInput {
+                rows_read: 2,
+                bytes_read: 8,
+                min_rows: 3
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Not enough data: read 2 rows (8 bytes), at least 3 required"
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! Dialect scoring algorithms.
+//!
+//! A `Sniffer` parses the sample with every candidate dialect and asks its
+//! `DialectScorer` to rank the resulting tables; the highest score wins.
+
+use crate::{CandidateTable, DataType, Dialect, Sniffer};
+use std::collections::HashMap;
+
+/// A pluggable algorithm for ranking candidate dialects
+pub trait DialectScorer: Send + Sync {
+    /// Short name of the algorithm
+    fn name(&self) -> &str;
+
+    /// Score `dialect`, given the raw `sample` and the table it parses into
+    ///
+    /// Higher is better; `f64::NEG_INFINITY` rejects the candidate.
+    fn score(
+        &self,
+        sniffer: &Sniffer,
+        sample: &str,
+        dialect: &Dialect,
+        table: &CandidateTable,
+    ) -> f64;
+
+    /// Whether every table with dropped rows scores `f64::NEG_INFINITY`
+    ///
+    /// Lets the sniffer skip parsing candidates whose rows cannot all have
+    /// the same width.
+    fn rejects_ragged_rows(&self) -> bool {
+        false
+    }
+}
+
+/// The Table Uniformity Method: type-weighted column uniformity minus an
+/// empty-field penalty, using the sniffer's `ScoringProfile`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TableUniformityScorer;
+
+impl DialectScorer for TableUniformityScorer {
+    fn name(&self) -> &str {
+        "tum"
+    }
+
+    fn rejects_ragged_rows(&self) -> bool {
+        true
+    }
+
+    fn score(
+        &self,
+        sniffer: &Sniffer,
+        _sample: &str,
+        _dialect: &Dialect,
+        table: &CandidateTable,
+    ) -> f64 {
+        sniffer.calculate_table_uniformity(table)
+    }
+}
+
+/// `CleverCSV`'s data consistency measure: pattern score times type score
+///
+/// Row patterns are approximated by field counts. The pattern score is
+/// `(1/K) * Σ N_k (L_k - 1) / L_k` over the `K` distinct row lengths `L_k`
+/// occurring `N_k` times, and the type score is the fraction of fields with a
+/// recognized (non-`Text`) type, including empty fields.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConsistencyScorer;
+
+impl DialectScorer for ConsistencyScorer {
+    fn name(&self) -> &str {
+        "clevercsv"
+    }
+
+    fn score(
+        &self,
+        _sniffer: &Sniffer,
+        _sample: &str,
+        _dialect: &Dialect,
+        table: &CandidateTable,
+    ) -> f64 {
+        let row_lengths = table.row_length_counts();
+        if row_lengths.is_empty() {
+            return f64::NEG_INFINITY;
+        }
+
+        let pattern_score = row_lengths
+            .iter()
+            .filter(|&(&length, _)| length > 0)
+            .map(|(&length, &count)| count as f64 * (length - 1) as f64 / length as f64)
+            .sum::<f64>()
+            / row_lengths.len() as f64;
+
+        let (known, total) = table
+            .column_type_counts()
+            .iter()
+            .flat_map(HashMap::iter)
+            .fold((0, 0), |(known, total), (data_type, &count)| {
+                let known = if *data_type == DataType::Text {
+                    known
+                } else {
+                    known + count
+                };
+                (known, total + count)
+            });
+        if total == 0 {
+            return f64::NEG_INFINITY;
+        }
+
+        // CleverCSV keeps a tiny floor so untyped tables still rank by pattern
+        let type_score = (known as f64 / total as f64).max(1e-10);
+        pattern_score * type_score
+    }
+}
+
+/// A heuristic compatible with Python's `csv.Sniffer`
+///
+/// Scores a delimiter by how consistently it occurs the same number of times
+/// per line (outside quotes), rewards quote characters seen wrapping fields,
+/// and breaks ties with `csv.Sniffer`'s preferred delimiter order.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrequencyScorer;
+
+impl FrequencyScorer {
+    /// `csv.Sniffer.preferred`
+    const PREFERRED: [u8; 5] = [b',', b'\t', b';', b' ', b':'];
+
+    /// Count `delimiter` outside `quote`-delimited spans of a line
+    fn count_outside_quotes(line: &str, delimiter: u8, quote: Option<u8>) -> usize {
+        let mut in_quotes = false;
+        let mut count = 0;
+        for b in line.bytes() {
+            if Some(b) == quote {
+                in_quotes = !in_quotes;
+            } else if b == delimiter && !in_quotes {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Number of lines where `quote` wraps a field, i.e. appears right after
+    /// a delimiter (or line start) and later right before one (or line end)
+    fn quote_evidence(lines: &[&str], delimiter: u8, quote: u8) -> usize {
+        lines
+            .iter()
+            .filter(|line| {
+                let bytes = line.as_bytes();
+                let opens = bytes
+                    .iter()
+                    .enumerate()
+                    .any(|(i, &b)| b == quote && (i == 0 || bytes[i - 1] == delimiter));
+                let closes = bytes.iter().enumerate().any(|(i, &b)| {
+                    b == quote && (i + 1 == bytes.len() || bytes[i + 1] == delimiter)
+                });
+                opens && closes
+            })
+            .count()
+    }
+}
+
+impl DialectScorer for FrequencyScorer {
+    fn name(&self) -> &str {
+        "python"
+    }
+
+    fn score(
+        &self,
+        _sniffer: &Sniffer,
+        sample: &str,
+        dialect: &Dialect,
+        _table: &CandidateTable,
+    ) -> f64 {
+        let lines: Vec<&str> = sample.lines().filter(|line| !line.is_empty()).collect();
+        if lines.is_empty() {
+            return f64::NEG_INFINITY;
+        }
+
+        // Mode of the per-line delimiter frequency, preferring higher
+        // frequencies on ties as csv.Sniffer does
+        let mut frequencies: HashMap<usize, usize> = HashMap::new();
+        for line in &lines {
+            let count = Self::count_outside_quotes(line, dialect.delimiter, dialect.quote_char);
+            *frequencies.entry(count).or_insert(0) += 1;
+        }
+        let (mode, mode_count) = frequencies
+            .into_iter()
+            .max_by_key(|&(frequency, lines)| (lines, frequency))
+            .unwrap_or_default();
+        if mode == 0 {
+            return f64::NEG_INFINITY;
+        }
+        let consistency = mode_count as f64 / lines.len() as f64;
+
+        let evidence = |quote: u8| Self::quote_evidence(&lines, dialect.delimiter, quote) > 0;
+        let quote_bonus = match dialect.quote_char {
+            Some(quote) if evidence(quote) => 0.1,
+            // Without quoting evidence, csv.Sniffer falls back to '"'
+            Some(b'"') if !evidence(b'\'') => 0.05,
+            _ => 0.0,
+        };
+
+        let preference = Self::PREFERRED
+            .iter()
+            .position(|&d| d == dialect.delimiter)
+            .map_or(0.0, |idx| 0.001 * (Self::PREFERRED.len() - idx) as f64);
+
+        consistency + quote_bonus + preference
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::SniffCache;
+
+    fn detect_with<S: DialectScorer + 'static>(scorer: S, data: &str) -> Dialect {
+        let mut sniffer = Sniffer::new();
+        sniffer.set_scorer(scorer);
+        sniffer.sniff_from_string(data).unwrap()
+    }
+
+    #[test]
+    fn test_scorers_detect_common_delimiters() {
+        let cases = [
+            ("name,age,city\nJohn,25,NYC\nJane,30,LA\nBob,35,SF", b','),
+            ("name;age;city\nJohn;25;NYC\nJane;30;LA\nBob;35;SF", b';'),
+            ("name\tage\tcity\nJohn\t25\tNYC\nJane\t30\tLA", b'\t'),
+            ("a|b|c\n1|2|3\n4|5|6\n7|8|9", b'|'),
+        ];
+
+        for (data, delimiter) in cases {
+            assert_eq!(detect_with(ConsistencyScorer, data).delimiter, delimiter);
+            assert_eq!(detect_with(FrequencyScorer, data).delimiter, delimiter);
+        }
+    }
+
+    #[test]
+    fn test_frequency_scorer_quotes() {
+        let data = "name,note\n'Doe, John','likes, commas'\n'Roe, Jane','plain'\n";
+        let dialect = detect_with(FrequencyScorer, data);
+
+        assert_eq!(dialect.delimiter, b',');
+        assert_eq!(dialect.quote_char, Some(b'\''));
+    }
+
+    #[test]
+    fn test_consistency_scorer_penalizes_ragged_rows() {
+        let sniffer = Sniffer::new();
+        let uniform = Dialect {
+            has_headers: false,
+            ..Dialect::default()
+        };
+        let score = |data: &str| {
+            let table = sniffer
+                .parse_with_dialect(data, &uniform, &SniffCache::new(data))
+                .unwrap();
+            ConsistencyScorer.score(&sniffer, data, &uniform, &table)
+        };
+
+        // Three rows of three typed fields: P = 3 * 2/3 = 2, T = 1
+        assert!((score("1,2,3\n4,5,6\n7,8,9") - 2.0).abs() < 1e-9);
+        assert!(score("1,2,3\n4,5\n7,8,9") < score("1,2,3\n4,5,6\n7,8,9"));
+    }
+}
@@ -0,0 +1,283 @@
+//! Scoring weights and penalty factors for the Table Uniformity Method.
+
+use crate::detector::TEXT_WEIGHT;
+use crate::{DataType, SnifferError, default_detectors};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Weights and penalty factors used to score candidate tables
+///
+/// Type weights are keyed by type name (`integer`, `date_time`, `text`, or a
+/// custom detector's name). Types without an entry use their detector's
+/// `TypeDetector::weight`, then `default_weight`. Empty fields never make up
+/// a column's dominant type; `empty_penalty` accounts for them instead.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringProfile {
+    /// Uniformity multiplier by dominant column type
+    ///
+    /// Deserialized entries are merged over the default weights, so a
+    /// profile only needs to list the weights it changes.
+    #[serde(deserialize_with = "merge_type_weights")]
+    pub type_weights: BTreeMap<String, f64>,
+    /// Multiplier for types with no entry in `type_weights` and no detector
+    pub default_weight: f64,
+    /// Penalty per unit of empty-field ratio
+    pub empty_penalty: f64,
+    /// Multiplier applied to the average column uniformity
+    pub row_consistency_bonus: f64,
+}
+
+impl Default for ScoringProfile {
+    fn default() -> Self {
+        // Built-in detectors carry the default weights; `Text` has no detector
+        let type_weights = default_detectors()
+            .iter()
+            .map(|detector| (detector.name().to_string(), detector.weight()))
+            .chain([(DataType::Text.name().to_string(), TEXT_WEIGHT)])
+            .collect();
+
+        Self {
+            type_weights,
+            default_weight: 1.0,
+            empty_penalty: 0.5,
+            row_consistency_bonus: 1.0,
+        }
+    }
+}
+
+impl ScoringProfile {
+    /// Names accepted by `ScoringProfile::preset`
+    pub const PRESETS: [&'static str; 3] = ["default", "sparse", "text-heavy"];
+
+    /// Profile for sparse data, where many empty fields are expected and
+    /// should barely count against a dialect
+    #[must_use]
+    pub fn sparse() -> Self {
+        Self {
+            empty_penalty: 0.1,
+            ..Self::default()
+        }
+    }
+
+    /// Profile for text-heavy data, where free-text columns are the norm and
+    /// should not lose out to an accidental split into numeric fragments
+    #[must_use]
+    pub fn text_heavy() -> Self {
+        let mut profile = Self::default();
+        for (name, weight) in &mut profile.type_weights {
+            *weight = if name == "text" { 1.0 } else { 1.05 };
+        }
+        profile
+    }
+
+    /// Look up a named preset (see `ScoringProfile::PRESETS`)
+    #[must_use]
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::default()),
+            "sparse" => Some(Self::sparse()),
+            "text-heavy" | "text_heavy" => Some(Self::text_heavy()),
+            _ => None,
+        }
+    }
+
+    /// Check that every weight and factor is a finite, non-negative number
+    pub fn validate(&self) -> Result<(), SnifferError> {
+        let factors = [
+            ("default_weight", self.default_weight),
+            ("empty_penalty", self.empty_penalty),
+            ("row_consistency_bonus", self.row_consistency_bonus),
+        ];
+        let weights = self
+            .type_weights
+            .iter()
+            .map(|(name, &weight)| (name.as_str(), weight));
+        match factors
+            .into_iter()
+            .chain(weights)
+            .find(|(_, value)| !value.is_finite() || *value < 0.0)
+        {
+            Some((name, value)) => Err(SnifferError::InvalidProfile(format!(
+                "{name} must be a finite, non-negative number, found {value}"
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Parse a profile from JSON
+    #[cfg(feature = "json")]
+    pub fn from_json_str(s: &str) -> Result<Self, SnifferError> {
+        let profile: Self =
+            serde_json::from_str(s).map_err(|e| SnifferError::InvalidProfile(e.to_string()))?;
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    /// Parse a profile from TOML
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(s: &str) -> Result<Self, SnifferError> {
+        let profile: Self =
+            toml::from_str(s).map_err(|e| SnifferError::InvalidProfile(e.to_string()))?;
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    /// Load a profile from a `.json` or `.toml` file
+    ///
+    /// Each format requires its cargo feature (`json` or `toml`).
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, SnifferError> {
+        let path = path.as_ref();
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            #[cfg(feature = "json")]
+            Some("json") => Self::from_json_str(&std::fs::read_to_string(path)?),
+            #[cfg(feature = "toml")]
+            Some("toml") => Self::from_toml_str(&std::fs::read_to_string(path)?),
+            _ => Err(SnifferError::InvalidProfile(format!(
+                "unsupported scoring profile format: {}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Serialize the profile as pretty-printed JSON
+    #[cfg(feature = "json")]
+    pub fn to_json_string(&self) -> Result<String, SnifferError> {
+        serde_json::to_string_pretty(self).map_err(|e| SnifferError::InvalidProfile(e.to_string()))
+    }
+
+    /// Serialize the profile as TOML
+    #[cfg(feature = "toml")]
+    pub fn to_toml_string(&self) -> Result<String, SnifferError> {
+        toml::to_string_pretty(self).map_err(|e| SnifferError::InvalidProfile(e.to_string()))
+    }
+
+    /// Save the profile to a `.json` or `.toml` file, which `ScoringProfile::load` reads back
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SnifferError> {
+        let path = path.as_ref();
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            #[cfg(feature = "json")]
+            Some("json") => Ok(std::fs::write(path, self.to_json_string()?)?),
+            #[cfg(feature = "toml")]
+            Some("toml") => Ok(std::fs::write(path, self.to_toml_string()?)?),
+            _ => Err(SnifferError::InvalidProfile(format!(
+                "unsupported scoring profile format: {}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Weight for a type name, if the profile defines one
+    pub(crate) fn type_weight(&self, name: &str) -> Option<f64> {
+        self.type_weights.get(name).copied()
+    }
+}
+
+fn merge_type_weights<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<String, f64>, D::Error> {
+    let mut weights = ScoringProfile::default().type_weights;
+    weights.extend(BTreeMap::<String, f64>::deserialize(deserializer)?);
+    Ok(weights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sniffer;
+
+    #[test]
+    fn test_presets() {
+        for name in ScoringProfile::PRESETS {
+            assert!(ScoringProfile::preset(name).is_some());
+        }
+        assert!(ScoringProfile::preset("unknown").is_none());
+        assert!(ScoringProfile::sparse().empty_penalty < ScoringProfile::default().empty_penalty);
+    }
+
+    #[test]
+    fn test_default_weights_come_from_detectors() {
+        let profile = ScoringProfile::default();
+        let detectors = default_detectors();
+        for detector in &detectors {
+            assert_eq!(
+                profile.type_weight(detector.name()),
+                Some(detector.weight())
+            );
+        }
+        assert_eq!(profile.type_weight("text"), Some(TEXT_WEIGHT));
+        assert_eq!(profile.type_weights.len(), detectors.len() + 1);
+    }
+
+    #[test]
+    fn test_profile_changes_score() {
+        let csv_data = "id,score,day\n10,1.5,2024-01-01\n11,,2024-01-02\n12,4.5,2024-01-03";
+        let mut sniffer = Sniffer::new();
+        let default_score = sniffer.detect_from_string(csv_data).unwrap().score;
+
+        sniffer.scoring_profile = ScoringProfile::sparse();
+        let sparse_score = sniffer.detect_from_string(csv_data).unwrap().score;
+        assert!(sparse_score > default_score);
+    }
+
+    #[test]
+    fn test_partial_profile_deserialization() {
+        let profile: ScoringProfile =
+            serde_json::from_str(r#"{"empty_penalty": 0.25, "type_weights": {"sku": 1.3}}"#)
+                .unwrap();
+        assert!((profile.empty_penalty - 0.25).abs() < f64::EPSILON);
+        assert_eq!(profile.type_weight("sku"), Some(1.3));
+        // Weights the file does not mention keep their defaults
+        assert_eq!(profile.type_weight("integer"), Some(1.2));
+        assert_eq!(profile.type_weight("text"), Some(0.8));
+        assert!((profile.row_consistency_bonus - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_weights() {
+        assert!(ScoringProfile::default().validate().is_ok());
+        for preset in ScoringProfile::PRESETS {
+            assert!(ScoringProfile::preset(preset).unwrap().validate().is_ok());
+        }
+
+        let mut profile = ScoringProfile::default();
+        profile.type_weights.insert("text".to_string(), f64::NAN);
+        assert!(matches!(
+            profile.validate(),
+            Err(SnifferError::InvalidProfile(message)) if message.contains("text")
+        ));
+        let profile = ScoringProfile {
+            default_weight: f64::INFINITY,
+            ..ScoringProfile::default()
+        };
+        assert!(profile.validate().is_err());
+
+        #[cfg(feature = "json")]
+        {
+            let negative = r#"{"type_weights": {"integer": -1.0}}"#;
+            assert!(matches!(
+                ScoringProfile::from_json_str(negative),
+                Err(SnifferError::InvalidProfile(_))
+            ));
+        }
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_load_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profile.toml");
+        std::fs::write(&path, "empty_penalty = 0.2\n\n[type_weights]\ntext = 0.9\n").unwrap();
+
+        let profile = ScoringProfile::load(&path).unwrap();
+        assert_eq!(profile.type_weight("text"), Some(0.9));
+        assert!(ScoringProfile::load(dir.path().join("profile.yaml")).is_err());
+
+        let saved = dir.path().join("saved.toml");
+        profile.save(&saved).unwrap();
+        assert_eq!(ScoringProfile::load(&saved).unwrap(), profile);
+    }
+}
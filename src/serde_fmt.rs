@@ -0,0 +1,122 @@
+//! Human-readable serde encodings for the byte-oriented `Dialect` fields.
+//!
+//! Delimiter, quote and escape bytes are written as one-character strings
+//! (`","`, `"\t"`), or as `"\xHH"` escapes for bytes outside ASCII, which
+//! have no one-character form. Line terminators are written as `"CRLF"`,
+//! `"LF"` or `"CR"`, and quote styles as their lowercase names.
+
+use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+fn byte_to_string(b: u8) -> String {
+    if b.is_ascii() {
+        char::from(b).to_string()
+    } else {
+        format!("\\x{b:02X}")
+    }
+}
+
+fn byte_from_str<E: Error>(s: &str) -> Result<u8, E> {
+    let mut chars = s.chars();
+    if let (Some(c), None) = (chars.next(), chars.next())
+        && c.is_ascii()
+    {
+        return Ok(c as u8);
+    }
+    s.strip_prefix("\\x")
+        .filter(|hex| hex.len() == 2)
+        .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        .filter(|b| !b.is_ascii())
+        .ok_or_else(|| {
+            E::custom(format!(
+                "expected a single ASCII character or a \\xHH escape, found {s:?}"
+            ))
+        })
+}
+
+pub mod byte {
+    use super::{Deserialize, Deserializer, Serializer, byte_from_str, byte_to_string};
+
+    pub fn serialize<S: Serializer>(value: &u8, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&byte_to_string(*value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+        byte_from_str(&String::deserialize(deserializer)?)
+    }
+}
+
+pub mod opt_byte {
+    use super::{Deserialize, Deserializer, Serializer, byte_from_str, byte_to_string};
+
+    #[allow(clippy::ref_option)]
+    pub fn serialize<S: Serializer>(value: &Option<u8>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(b) => serializer.serialize_some(&byte_to_string(*b)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u8>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| byte_from_str(&s))
+            .transpose()
+    }
+}
+
+pub mod terminator {
+    use super::{Deserialize, Deserializer, Error, Serializer, byte_from_str, byte_to_string};
+    use csv::Terminator;
+
+    pub fn serialize<S: Serializer>(value: &Terminator, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Terminator::CRLF => serializer.serialize_str("CRLF"),
+            Terminator::Any(b'\n') => serializer.serialize_str("LF"),
+            Terminator::Any(b'\r') => serializer.serialize_str("CR"),
+            Terminator::Any(b) => serializer.serialize_str(&byte_to_string(*b)),
+            _ => Err(serde::ser::Error::custom("unsupported line terminator")),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Terminator, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        match s.as_str() {
+            "CRLF" | "\r\n" => Ok(Terminator::CRLF),
+            "LF" => Ok(Terminator::Any(b'\n')),
+            "CR" => Ok(Terminator::Any(b'\r')),
+            other => byte_from_str(other)
+                .map(Terminator::Any)
+                .map_err(|_: D::Error| {
+                    D::Error::custom(format!("invalid line terminator {other:?}"))
+                }),
+        }
+    }
+}
+
+pub mod quote_style {
+    use super::{Deserialize, Deserializer, Error, Serializer};
+    use csv::QuoteStyle;
+
+    pub fn serialize<S: Serializer>(value: &QuoteStyle, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match value {
+            QuoteStyle::Always => "always",
+            QuoteStyle::Necessary => "necessary",
+            QuoteStyle::NonNumeric => "non_numeric",
+            QuoteStyle::Never => "never",
+            _ => return Err(serde::ser::Error::custom("unsupported quote style")),
+        })
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<QuoteStyle, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        match s.as_str() {
+            "always" => Ok(QuoteStyle::Always),
+            "necessary" => Ok(QuoteStyle::Necessary),
+            "non_numeric" => Ok(QuoteStyle::NonNumeric),
+            "never" => Ok(QuoteStyle::Never),
+            other => Err(D::Error::unknown_variant(
+                other,
+                &["always", "necessary", "non_numeric", "never"],
+            )),
+        }
+    }
+}
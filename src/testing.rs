@@ -0,0 +1,371 @@
+//! Synthetic CSV generation for property testing (requires the `testing` feature).
+//!
+//! Strategies produce random tables with known column types, written with a
+//! random `Dialect`, so tests can check that sniffing recovers what was
+//! written. Everything is built from proptest strategies, so failing cases
+//! shrink to small tables.
+
+use crate::{DataType, Dialect, classify_field};
+use proptest::prelude::*;
+use std::fmt;
+
+/// Bounds and options for generated files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneratorConfig {
+    pub min_rows: usize,
+    pub max_rows: usize,
+    pub min_columns: usize,
+    pub max_columns: usize,
+    /// Allow an escape character (with doubled quotes disabled)
+    pub escape: bool,
+    /// Allow CRLF line endings
+    pub crlf: bool,
+    /// Allow `#` preamble lines before the table; they contain no
+    /// delimiters, so they never have the shape of a `#` header row
+    pub preamble: bool,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            min_rows: 3,
+            max_rows: 20,
+            min_columns: 2,
+            max_columns: 6,
+            escape: true,
+            crlf: true,
+            preamble: true,
+        }
+    }
+}
+
+/// A table of string values with known column types
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntheticTable {
+    pub headers: Vec<String>,
+    pub column_types: Vec<DataType>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// A generated CSV file and how it was written
+#[derive(Debug, Clone)]
+pub struct SyntheticCsv {
+    pub table: SyntheticTable,
+    /// The dialect used to write `data`; `has_headers` records whether the
+    /// header row was written
+    pub dialect: Dialect,
+    /// Lines written before the table
+    pub preamble: Vec<String>,
+    pub data: String,
+}
+
+impl SyntheticCsv {
+    /// Whether any field in `data` was written quoted
+    #[must_use]
+    pub fn uses_quotes(&self) -> bool {
+        self.dialect
+            .quote_char
+            .is_some_and(|quote| self.data.as_bytes().contains(&quote))
+    }
+
+    /// Whether a quote character in `data` was written escaped
+    #[must_use]
+    pub fn uses_escape(&self) -> bool {
+        self.dialect
+            .escape
+            .zip(self.dialect.quote_char)
+            .is_some_and(|(escape, quote)| {
+                self.data
+                    .as_bytes()
+                    .windows(2)
+                    .any(|pair| pair == [escape, quote])
+            })
+    }
+}
+
+/// Failure report: the dialect, column types and the file with line endings
+/// made visible
+impl fmt::Display for SyntheticCsv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "dialect: {}", self.dialect)?;
+        writeln!(f, "terminator: {:?}", self.dialect.terminator)?;
+        writeln!(f, "quoting: {:?}", self.dialect.quoting)?;
+        let types: Vec<&str> = self.table.column_types.iter().map(DataType::name).collect();
+        writeln!(f, "column types: {}", types.join(", "))?;
+        writeln!(f, "data:")?;
+        for line in self.data.split_inclusive('\n') {
+            writeln!(f, "  {}", line.replace('\r', "\\r").replace('\n', "\\n"))?;
+        }
+        Ok(())
+    }
+}
+
+impl SyntheticTable {
+    /// Write the table with `dialect`, after the given preamble lines
+    ///
+    /// Uses the dialect's delimiter, quote character, quoting style, escape
+    /// and terminator; the header row is written when `has_headers` is set.
+    #[must_use]
+    pub fn write(&self, dialect: &Dialect, preamble: &[String]) -> String {
+        let newline = match dialect.terminator {
+            csv::Terminator::CRLF => "\r\n",
+            _ => "\n",
+        };
+        let mut data: String = preamble
+            .iter()
+            .map(|line| format!("{line}{newline}"))
+            .collect();
+
+        let mut builder = csv::WriterBuilder::new();
+        builder
+            .delimiter(dialect.delimiter)
+            .terminator(dialect.terminator)
+            .quote_style(dialect.quoting);
+        if let Some(quote) = dialect.quote_char {
+            builder.quote(quote);
+        }
+        if let Some(escape) = dialect.escape {
+            builder.escape(escape).double_quote(false);
+        }
+
+        let mut writer = builder.from_writer(Vec::new());
+        if dialect.has_headers {
+            writer
+                .write_record(&self.headers)
+                .expect("writing to a Vec cannot fail");
+        }
+        for row in &self.rows {
+            writer
+                .write_record(row)
+                .expect("writing to a Vec cannot fail");
+        }
+        let bytes = writer.into_inner().expect("writing to a Vec cannot fail");
+        data.push_str(std::str::from_utf8(&bytes).expect("fields are UTF-8"));
+        data
+    }
+}
+
+/// Delimiters the sniffer considers, except space (which it only tries with quoting)
+const DELIMITERS: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+/// A random dialect within `config`
+pub fn arb_dialect(config: GeneratorConfig) -> impl Strategy<Value = Dialect> {
+    let escape = if config.escape {
+        prop_oneof![3 => Just(None), 1 => Just(Some(b'\\'))].boxed()
+    } else {
+        Just(None).boxed()
+    };
+    let terminator = if config.crlf {
+        prop_oneof![
+            Just(csv::Terminator::Any(b'\n')),
+            Just(csv::Terminator::CRLF)
+        ]
+        .boxed()
+    } else {
+        Just(csv::Terminator::Any(b'\n')).boxed()
+    };
+    let quoting = prop_oneof![
+        Just((Some(b'"'), csv::QuoteStyle::Necessary)),
+        Just((Some(b'"'), csv::QuoteStyle::Always)),
+        Just((Some(b'"'), csv::QuoteStyle::NonNumeric)),
+        Just((Some(b'\''), csv::QuoteStyle::Necessary)),
+        Just((Some(b'\''), csv::QuoteStyle::Always)),
+        Just((Some(b'\''), csv::QuoteStyle::NonNumeric)),
+        Just((None, csv::QuoteStyle::Never)),
+    ];
+
+    (
+        proptest::sample::select(&DELIMITERS[..]),
+        quoting,
+        escape,
+        terminator,
+        any::<bool>(),
+    )
+        .prop_map(
+            |(delimiter, (quote_char, quoting), escape, terminator, has_headers)| Dialect {
+                delimiter,
+                quote_char,
+                // An escape character is only meaningful with quoting
+                escape: escape.filter(|_| quote_char.is_some()),
+                has_headers,
+                terminator,
+                quoting,
+            },
+        )
+}
+
+/// Values of one column type
+///
+/// Text may contain `delimiter` and the quote character only when the
+/// dialect quotes fields, since unquoted they would change the table's shape.
+fn arb_value(data_type: &DataType, delimiter: u8, quote: Option<u8>) -> BoxedStrategy<String> {
+    match data_type {
+        // Small integers could be read as booleans ("1"/"0")
+        DataType::Integer => (10i64..100_000).prop_map(|n| n.to_string()).boxed(),
+        DataType::Float => (0u32..100_000)
+            .prop_map(|n| format!("{}.{:02}", n / 100, n % 100))
+            .boxed(),
+        DataType::Boolean => prop_oneof![Just("true"), Just("false")]
+            .prop_map(String::from)
+            .boxed(),
+        DataType::Date => (2000u32..2030, 1u32..=12, 1u32..=28)
+            .prop_map(|(y, m, d)| format!("{y}-{m:02}-{d:02}"))
+            .boxed(),
+        _ => {
+            let separators: Vec<char> = [' ', ',', ';', '|', '-']
+                .into_iter()
+                .filter(|&c| quote.is_some() || c != delimiter as char)
+                .chain(quote.map(char::from))
+                .collect();
+            (
+                "[A-Za-z]{2,8}",
+                proptest::collection::vec(
+                    (proptest::sample::select(separators), "[A-Za-z]{2,8}"),
+                    0..3,
+                ),
+            )
+                .prop_map(|(first, rest)| {
+                    rest.into_iter().fold(first, |mut text, (sep, word)| {
+                        text.push(sep);
+                        text.push_str(&word);
+                        text
+                    })
+                })
+                // Words like "no" or "on" would be read as booleans
+                .prop_filter("text reads as a boolean", |text| {
+                    !classify_field(text).contains(&DataType::Boolean)
+                })
+                .boxed()
+        }
+    }
+}
+
+/// A random table whose values can be written with `dialect`
+///
+/// Every table has at least one numeric column, so that a header row (a row
+/// of names) is distinguishable from data.
+pub fn arb_table(
+    config: GeneratorConfig,
+    dialect: &Dialect,
+) -> impl Strategy<Value = SyntheticTable> + use<> {
+    let column_type = prop_oneof![
+        Just(DataType::Integer),
+        Just(DataType::Float),
+        Just(DataType::Boolean),
+        Just(DataType::Date),
+        Just(DataType::Text),
+    ];
+    let numeric = prop_oneof![Just(DataType::Integer), Just(DataType::Float)];
+    let others = config.min_columns.saturating_sub(1)..config.max_columns.max(1);
+    let (delimiter, quote) = (dialect.delimiter, dialect.quote_char);
+
+    (
+        numeric,
+        proptest::collection::vec(column_type, others),
+        any::<prop::sample::Index>(),
+    )
+        .prop_flat_map(move |(numeric, mut column_types, position)| {
+            column_types.insert(position.index(column_types.len() + 1), numeric);
+            let headers = proptest::collection::vec("[a-z]{3,8}", column_types.len());
+            let row: Vec<BoxedStrategy<String>> = column_types
+                .iter()
+                .map(|data_type| arb_value(data_type, delimiter, quote))
+                .collect();
+            (
+                Just(column_types),
+                headers,
+                proptest::collection::vec(row, config.min_rows..=config.max_rows),
+            )
+        })
+        .prop_map(|(column_types, headers, rows)| SyntheticTable {
+            headers,
+            column_types,
+            rows,
+        })
+}
+
+/// A random table written with a random dialect
+pub fn arb_csv(config: GeneratorConfig) -> impl Strategy<Value = SyntheticCsv> {
+    let preamble = if config.preamble {
+        proptest::collection::vec("# [A-Za-z0-9 .:-]{0,30}", 0..3).boxed()
+    } else {
+        Just(Vec::new()).boxed()
+    };
+
+    (arb_dialect(config), preamble).prop_flat_map(move |(dialect, preamble)| {
+        arb_table(config, &dialect).prop_map(move |table| SyntheticCsv {
+            data: table.write(&dialect, &preamble),
+            table,
+            dialect: dialect.clone(),
+            preamble: preamble.clone(),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sniffer;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(256))]
+
+        /// Sniffing a generated file recovers the delimiter, the quote and
+        /// escape characters when they were written, the line terminator,
+        /// whether there is a header and how many preamble lines precede the
+        /// table
+        #[test]
+        fn prop_sniffer_recovers_dialect(csv in arb_csv(GeneratorConfig::default())) {
+            let detected = Sniffer::new().detect_from_string(&csv.data);
+            prop_assert!(detected.is_ok(), "no dialect detected\n{csv}");
+            let detected = detected.unwrap();
+
+            prop_assert_eq!(detected.dialect.delimiter, csv.dialect.delimiter, "\n{}", csv);
+            if csv.uses_quotes() {
+                prop_assert_eq!(detected.dialect.quote_char, csv.dialect.quote_char, "\n{}", csv);
+            }
+            if csv.uses_escape() {
+                prop_assert_eq!(detected.dialect.escape, csv.dialect.escape, "\n{}", csv);
+            }
+            prop_assert!(
+                matches!(
+                    (detected.dialect.terminator, csv.dialect.terminator),
+                    (csv::Terminator::CRLF, csv::Terminator::CRLF)
+                        | (csv::Terminator::Any(b'\n'), csv::Terminator::Any(b'\n'))
+                ),
+                "terminator {:?}\n{}",
+                detected.dialect.terminator,
+                csv
+            );
+            prop_assert_eq!(detected.dialect.has_headers, csv.dialect.has_headers, "\n{}", csv);
+            prop_assert_eq!(detected.preamble_rows, csv.preamble.len(), "\n{}", csv);
+        }
+
+        /// Reading a generated file with the sniffed dialect returns the rows
+        /// that were written, whichever line terminator was used
+        #[test]
+        fn prop_sniffed_dialect_reads_rows(csv in arb_csv(GeneratorConfig::default())) {
+            let rows = Sniffer::new().deserialize_from_string::<Vec<String>>(&csv.data);
+            prop_assert!(rows.is_ok(), "{}\n{csv}", rows.unwrap_err());
+            prop_assert_eq!(rows.unwrap(), csv.table.rows.clone(), "\n{}", csv);
+        }
+    }
+
+    #[test]
+    fn test_write_round_trip() {
+        let table = SyntheticTable {
+            headers: vec!["id".to_string(), "name".to_string()],
+            column_types: vec![DataType::Integer, DataType::Text],
+            rows: vec![vec!["10".to_string(), "Doe, Jane".to_string()]],
+        };
+        let dialect = Dialect {
+            terminator: csv::Terminator::CRLF,
+            ..Dialect::default()
+        };
+
+        assert_eq!(
+            table.write(&dialect, &["# export".to_string()]),
+            "# export\r\nid,name\r\n10,\"Doe, Jane\"\r\n"
+        );
+    }
+}
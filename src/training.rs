@@ -0,0 +1,363 @@
+//! Fitting `ScoringProfile` weights to a labeled corpus (requires the `train`
+//! feature).
+//!
+//! Every candidate table is reduced once to the features the Table Uniformity
+//! Method scores (dominant type and uniformity per column, empty-field ratio),
+//! so each trial profile is cheap to evaluate. Weights are then fitted by
+//! coordinate search, maximizing the number of files whose annotated dialect
+//! scores highest.
+
+use crate::{
+    CandidateTable, DataType, LabeledSample, Scored, ScoringProfile, Sniffer, best_candidate,
+    quote_occurs, split_preamble,
+};
+use std::collections::BTreeSet;
+
+/// Settings for `Sniffer::train_profile`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrainingOptions {
+    /// Maximum number of sweeps over all parameters
+    pub max_iterations: usize,
+    /// Initial additive step for each parameter
+    pub initial_step: f64,
+    /// The search stops once the step has been halved below this
+    pub min_step: f64,
+    /// Upper bound for every fitted parameter (the lower bound is 0)
+    pub max_weight: f64,
+}
+
+impl Default for TrainingOptions {
+    fn default() -> Self {
+        Self {
+            max_iterations: 100,
+            initial_step: 0.4,
+            min_step: 0.01,
+            max_weight: 5.0,
+        }
+    }
+}
+
+/// Outcome of `Sniffer::train_profile`
+#[derive(Debug, Clone)]
+pub struct TrainingReport {
+    /// The fitted profile
+    pub profile: ScoringProfile,
+    /// Number of files used for training
+    pub examples: usize,
+    /// Files skipped because they could not be sampled or no candidate
+    /// dialect matched their annotation
+    pub skipped: usize,
+    /// Accuracy of the sniffer's original profile on the training files
+    pub initial_accuracy: f64,
+    /// Accuracy of the fitted profile on the training files
+    pub accuracy: f64,
+    /// Number of parameter sweeps performed
+    pub iterations: usize,
+}
+
+/// Scoring inputs of one candidate table
+struct TableFeatures {
+    /// Dominant type name and uniformity of each non-empty column
+    columns: Vec<(String, f64)>,
+    empty_ratio: f64,
+    /// Whether the candidate's quote character occurs in the data, which
+    /// breaks ties between equal scores
+    quoted: bool,
+}
+
+impl TableFeatures {
+    fn score(&self, profile: &ScoringProfile) -> f64 {
+        let total: f64 = self
+            .columns
+            .iter()
+            .map(|(name, uniformity)| {
+                uniformity * profile.type_weight(name).unwrap_or(profile.default_weight)
+            })
+            .sum();
+        let avg_uniformity = total / self.columns.len() as f64;
+        avg_uniformity.mul_add(
+            profile.row_consistency_bonus,
+            -self.empty_ratio * profile.empty_penalty,
+        )
+    }
+}
+
+/// A training file: its accepted candidates and which of them is correct
+struct Example {
+    candidates: Vec<TableFeatures>,
+    truth: usize,
+}
+
+impl Example {
+    /// Whether the annotated candidate wins, and its margin over the best
+    /// wrong candidate
+    fn evaluate(&self, profile: &ScoringProfile) -> (bool, f64) {
+        let scores: Vec<f64> = self.candidates.iter().map(|c| c.score(profile)).collect();
+        let winner = best_candidate(scores.iter().copied().map(Some), |idx| {
+            self.candidates[idx].quoted
+        });
+        let truth = scores[self.truth];
+        let rival = scores
+            .iter()
+            .enumerate()
+            .filter(|&(idx, _)| idx != self.truth)
+            .map(|(_, &score)| score)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        (winner == Some(self.truth), (truth - rival).clamp(-1.0, 1.0))
+    }
+}
+
+/// Number of correct files plus a small bonus for wider margins, so that
+/// ties in accuracy are broken towards more confident profiles
+fn objective(examples: &[Example], profile: &ScoringProfile) -> (usize, f64) {
+    let mut correct = 0;
+    let mut margin = 0.0;
+    for example in examples {
+        let (wins, example_margin) = example.evaluate(profile);
+        correct += usize::from(wins);
+        margin += example_margin;
+    }
+    (correct, margin / examples.len().max(1) as f64)
+}
+
+fn better(a: (usize, f64), b: (usize, f64)) -> bool {
+    a.0 > b.0 || (a.0 == b.0 && a.1 > b.1 + 1e-9)
+}
+
+/// A fitted parameter of the profile
+#[derive(Clone)]
+enum Param {
+    TypeWeight(String),
+    EmptyPenalty,
+}
+
+impl Param {
+    fn get(&self, profile: &ScoringProfile) -> f64 {
+        match self {
+            Self::TypeWeight(name) => profile.type_weights[name],
+            Self::EmptyPenalty => profile.empty_penalty,
+        }
+    }
+
+    fn set(&self, profile: &mut ScoringProfile, value: f64) {
+        match self {
+            Self::TypeWeight(name) => {
+                profile.type_weights.insert(name.clone(), value);
+            }
+            Self::EmptyPenalty => profile.empty_penalty = value,
+        }
+    }
+}
+
+impl Sniffer {
+    /// Reduce an accepted candidate table to its TUM features
+    fn table_features(&self, table: &CandidateTable, quoted: bool) -> Option<TableFeatures> {
+        if self.calculate_table_uniformity(table) == f64::NEG_INFINITY {
+            return None;
+        }
+
+        let columns = table
+            .column_type_counts
+            .iter()
+            .filter_map(|type_counts| {
+                let total: usize = type_counts.values().sum();
+                if total == 0 {
+                    return None;
+                }
+                let (data_type, count) =
+                    Self::dominant_type(type_counts).unwrap_or((DataType::Text, 0));
+                Some((data_type.name().to_string(), count as f64 / total as f64))
+            })
+            .collect();
+        let empty_ratio = table.empty_count() as f64 / (table.num_rows * table.num_columns) as f64;
+
+        Some(TableFeatures {
+            columns,
+            empty_ratio,
+            quoted,
+        })
+    }
+
+    /// Fit type weights and the empty-field penalty to a labeled corpus
+    ///
+    /// Starts from the sniffer's `scoring_profile` and uses the sniffer's
+    /// detectors and `max_rows`. Each file is sampled and its candidates
+    /// evaluated as in `Sniffer::detect`, so only the candidates detection
+    /// compares are weighed, and ties are broken the same way; files that
+    /// cannot be sampled are skipped. `row_consistency_bonus` is kept fixed:
+    /// scaling it and every other parameter by the same factor never changes
+    /// a ranking. The returned profile has an explicit weight for every type
+    /// seen.
+    #[must_use]
+    pub fn train_profile(
+        &self,
+        corpus: &[LabeledSample],
+        options: &TrainingOptions,
+    ) -> TrainingReport {
+        let mut examples = Vec::new();
+        let mut type_names = BTreeSet::new();
+        for sample in corpus {
+            let Ok(data) = self.read_sample(sample.data.as_bytes()) else {
+                continue;
+            };
+            let (_, data) = split_preamble(&data);
+            let mut candidates = Vec::new();
+            let mut truth = None;
+            for (dialect, outcome) in self.evaluate_candidates(data).candidates {
+                let Scored::Parsed(table, score) = outcome else {
+                    continue;
+                };
+                let quoted = quote_occurs(data, &dialect);
+                let Some(features) = self
+                    .table_features(&table, quoted)
+                    .filter(|_| score != f64::NEG_INFINITY)
+                else {
+                    continue;
+                };
+                if truth.is_none() && sample.matches(&dialect) {
+                    truth = Some(candidates.len());
+                }
+                type_names.extend(features.columns.iter().map(|(name, _)| name.clone()));
+                candidates.push(features);
+            }
+            if let Some(truth) = truth {
+                examples.push(Example { candidates, truth });
+            }
+        }
+
+        // Pin down every weight the corpus exercises, resolved as the sniffer would
+        let mut profile = self.scoring_profile.clone();
+        for name in &type_names {
+            if profile.type_weight(name).is_none() {
+                let weight = self
+                    .detectors
+                    .iter()
+                    .find(|detector| detector.name() == name)
+                    .map_or(profile.default_weight, |detector| detector.weight());
+                profile.type_weights.insert(name.clone(), weight);
+            }
+        }
+
+        let params: Vec<Param> = type_names
+            .into_iter()
+            .map(Param::TypeWeight)
+            .chain([Param::EmptyPenalty])
+            .collect();
+
+        let initial = objective(&examples, &profile);
+        let mut best = initial;
+        let mut step = options.initial_step;
+        let mut iterations = 0;
+        while iterations < options.max_iterations && step >= options.min_step {
+            iterations += 1;
+            let mut improved = false;
+            for param in &params {
+                let current = param.get(&profile);
+                for delta in [step, -step] {
+                    let value = (current + delta).clamp(0.0, options.max_weight);
+                    let mut trial = profile.clone();
+                    param.set(&mut trial, value);
+                    let score = objective(&examples, &trial);
+                    if better(score, best) {
+                        best = score;
+                        profile = trial;
+                        improved = true;
+                        break;
+                    }
+                }
+            }
+            if !improved {
+                step /= 2.0;
+            }
+        }
+
+        let accuracy = |correct: usize| correct as f64 / examples.len().max(1) as f64;
+        TrainingReport {
+            profile,
+            examples: examples.len(),
+            skipped: corpus.len() - examples.len(),
+            initial_accuracy: accuracy(initial.0),
+            accuracy: accuracy(best.0),
+            iterations,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Dialect;
+
+    fn sample(data: &str, delimiter: u8) -> LabeledSample {
+        LabeledSample {
+            path: "sample.csv".into(),
+            data: data.to_string(),
+            dialect: Dialect {
+                delimiter,
+                ..Dialect::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_features_match_tum_score() {
+        let sniffer = Sniffer::new();
+        let data = "id,score,day\n10,1.5,2024-01-01\n11,,2024-01-02\n12,4.5,note";
+        for (_, table) in sniffer.candidate_tables(data, None).0 {
+            if let Some(features) = sniffer.table_features(&table, false) {
+                let mut profile = sniffer.scoring_profile.clone();
+                for (name, _) in &features.columns {
+                    profile
+                        .type_weights
+                        .entry(name.clone())
+                        .or_insert(profile.default_weight);
+                }
+                let expected = sniffer.calculate_table_uniformity(&table);
+                assert!((features.score(&profile) - expected).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_train_profile() {
+        let corpus = [
+            sample("name,age,city\nJohn,25,NYC\nJane,30,LA\nBob,35,SF", b','),
+            sample("a;b;c\n1;2;3\n4;5;6\n7;8;9", b';'),
+            sample("x\ty\n1.5\t2024-01-01\n2.5\t2024-01-02", b'\t'),
+            sample("a:b\n1:2\n3:4", b':'),
+        ];
+        let sniffer = Sniffer::new();
+        let report = sniffer.train_profile(&corpus, &TrainingOptions::default());
+
+        assert_eq!(report.examples, 3);
+        assert_eq!(report.skipped, 1);
+        assert!(report.accuracy >= report.initial_accuracy);
+        assert!(report.profile.type_weight("integer").is_some());
+
+        let mut trained = Sniffer::new();
+        trained.scoring_profile = report.profile;
+        assert_eq!(
+            trained
+                .sniff_from_string(&corpus[1].data)
+                .unwrap()
+                .delimiter,
+            b';'
+        );
+    }
+
+    #[test]
+    fn test_training_sees_what_detection_sees() {
+        // A `#` preamble, CRLF line endings and a quote tie between `"` and `'`
+        let data = "# exported 2024-01-05\r\nid,note\r\n1,'a, b'\r\n2,'c, d'\r\n3,'e, f'\r\n";
+        let mut quoted = sample(data, b',');
+        quoted.dialect.quote_char = Some(b'\'');
+        let corpus = [quoted];
+
+        let sniffer = Sniffer::new();
+        assert!(corpus[0].matches(&sniffer.sniff(data.as_bytes()).unwrap()));
+        let report = sniffer.train_profile(&corpus, &TrainingOptions::default());
+        assert_eq!(report.examples, 1);
+        assert!((report.initial_accuracy - 1.0).abs() < f64::EPSILON);
+    }
+}